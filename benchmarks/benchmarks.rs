@@ -1,268 +1,245 @@
-fn main() {
-    // Run registered benchmarks.
-    divan::main();
+fn main() -> ExitCode {
+    let args = match BaselineArgs::parse(std::env::args_os().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if args.save.is_none() && args.compare.is_none() {
+        // Run registered benchmarks.
+        divan::main();
+        return ExitCode::SUCCESS;
+    }
+    baseline(&args).unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        ExitCode::FAILURE
+    })
 }
 
-use lib::days::*;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, ExitCode, Stdio};
 
-const SAMPLE_COUNT: u32 = 100;
-const SAMPLE_SIZE: u32 = 100;
-
-mod test_input {
-    use super::*;
-    use divan::bench;
-    use divan::black_box;
-
-    const INPUT_D1_PART1_TEST: &str = include_str!("../res/day1_part1_test.txt");
-    const INPUT_D1_PART2_TEST: &str = include_str!("../res/day1_part2_test.txt");
-    const INPUT_D2_TEST: &str = include_str!("../res/day2_test.txt");
-    const INPUT_D3_TEST: &str = include_str!("../res/day3_test.txt");
-    const INPUT_D4_TEST: &str = include_str!("../res/day4_test.txt");
-    const INPUT_D5_TEST: &str = include_str!("../res/day5_test.txt");
-    const INPUT_D6_TEST: &str = include_str!("../res/day6_test.txt");
-    const INPUT_D7_TEST: &str = include_str!("../res/day7_test.txt");
-    const INPUT_D8_PART1_TEST: &str = include_str!("../res/day8_part1_test.txt");
-    const INPUT_D8_PART2_TEST: &str = include_str!("../res/day8_part2_test.txt");
-    const INPUT_D9_TEST: &str = include_str!("../res/day9_test.txt");
-    const INPUT_D10_PART1_TEST: &str = include_str!("../res/day10_part1_test2.txt");
-    const INPUT_D10_PART2_TEST: &str = include_str!("../res/day10_part2_test3.txt");
-    const INPUT_D11_TEST: &str = include_str!("../res/day11_test.txt");
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day01_part1() -> u64 {
-        day1::part1(black_box(INPUT_D1_PART1_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day01_part2() -> u64 {
-        day1::part2(black_box(INPUT_D1_PART2_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day02_part1() -> u64 {
-        day2::part1(black_box(INPUT_D2_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day02_part2() -> u64 {
-        day2::part2(black_box(INPUT_D2_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day03_part1() -> u64 {
-        day3::part1(black_box(INPUT_D3_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day03_part2() -> u64 {
-        day3::part2(black_box(INPUT_D3_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day04_part1() -> u64 {
-        day4::part1(black_box(INPUT_D4_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day04_part2() -> u64 {
-        day4::part2(black_box(INPUT_D4_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day05_part1() -> Option<i64> {
-        day5::part1(black_box(INPUT_D5_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day05_part2() -> Option<i64> {
-        day5::part2(black_box(INPUT_D5_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day06_part1() -> u32 {
-        day6::part1(black_box(INPUT_D6_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day06_part2() -> u32 {
-        day6::part2(black_box(INPUT_D6_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day07_part1() -> u64 {
-        day7::part1(black_box(INPUT_D7_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day07_part2() -> u64 {
-        day7::part2(black_box(INPUT_D7_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE / 2)]
-    fn day08_part1() -> u64 {
-        day8::part1(black_box(INPUT_D8_PART1_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE / 2)]
-    fn day08_part2() -> u64 {
-        day8::part2(black_box(INPUT_D8_PART2_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day09_part1() -> i64 {
-        day9::part1(black_box(INPUT_D9_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day09_part2() -> i64 {
-        day9::part2(black_box(INPUT_D9_TEST))
-    }
+use divan::Bencher;
+use lib::baseline::{Baseline, Verdict};
+use lib::inputs::{InputId, Inputs};
+use lib::registry::{Day, Solution};
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE / 10)]
-    fn day10_part1() -> u64 {
-        day10::part1(black_box(INPUT_D10_PART1_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE / 10)]
-    fn day10_part2() -> u64 {
-        day10::part2(black_box(INPUT_D10_PART2_TEST))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day11_part1() -> u64 {
-        day11::part1(black_box(INPUT_D11_TEST))
-    }
+const SAMPLE_COUNT: u32 = 100;
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day11_part2() -> u64 {
-        day11::part2_expand_999_999(black_box(INPUT_D11_TEST))
-    }
+/// Reports allocation counts and sizes next to the timings of every benchmark.
+#[global_allocator]
+static ALLOC: divan::AllocProfiler = divan::AllocProfiler::system();
+
+// Benchmarks are generated from the registry and named like `real::solve::day05_part2` or
+// `example::parse::day10/part2_test1`, so that `cargo bench -- day05`, `cargo bench -- real::` or
+// `cargo bench -- 'real::parse::day0[5-9]'` select a subset.
+
+/// The options this harness adds to those of divan, to save the results as a named baseline and compare later runs
+/// against it, e.g. `cargo bench -- --save-baseline main` and `cargo bench -- --baseline main --threshold 10 real::`.
+struct BaselineArgs {
+    /// Save the results as the baseline NAME in `target/baselines`, replacing an earlier baseline of that name.
+    save: Option<String>,
+    /// Compare the results with the baseline NAME saved before, failing if any median got slower than `threshold`.
+    compare: Option<String>,
+    /// Flag medians changing by more than this many percent compared to the baseline.
+    threshold: f64,
+    /// All other arguments, passed on to divan.
+    divan: Vec<OsString>,
 }
 
-mod real_input {
-    use super::*;
-    use divan::bench;
-    use divan::black_box;
-
-    const INPUT_D1: &str = include_str!("../res/day1.txt");
-    const INPUT_D2: &str = include_str!("../res/day2.txt");
-    const INPUT_D3: &str = include_str!("../res/day3.txt");
-    const INPUT_D4: &str = include_str!("../res/day4.txt");
-    const INPUT_D5: &str = include_str!("../res/day5.txt");
-    const INPUT_D6: &str = include_str!("../res/day6.txt");
-    const INPUT_D7: &str = include_str!("../res/day7.txt");
-    const INPUT_D8: &str = include_str!("../res/day8.txt");
-    const INPUT_D9: &str = include_str!("../res/day9.txt");
-    const INPUT_D10: &str = include_str!("../res/day10.txt");
-    const INPUT_D11: &str = include_str!("../res/day11.txt");
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day01_part1() -> u64 {
-        day1::part1(black_box(INPUT_D1))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day01_part2() -> u64 {
-        day1::part2(black_box(INPUT_D1))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day02_part1() -> u64 {
-        day2::part1(black_box(INPUT_D2))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day02_part2() -> u64 {
-        day2::part2(black_box(INPUT_D2))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day03_part1() -> u64 {
-        day3::part1(black_box(INPUT_D3))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day03_part2() -> u64 {
-        day3::part2(black_box(INPUT_D3))
-    }
-
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day04_part1() -> u64 {
-        day4::part1(black_box(INPUT_D4))
+impl BaselineArgs {
+    fn parse(mut args: impl Iterator<Item = OsString>) -> Result<Self, String> {
+        let mut parsed = Self {
+            save: None,
+            compare: None,
+            threshold: 5.0,
+            divan: Vec::new(),
+        };
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.to_str().and_then(|arg| arg.split_once('=')) {
+                Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+                None => (arg.to_string_lossy().into_owned(), None),
+            };
+            if !matches!(flag.as_str(), "--save-baseline" | "--baseline" | "--threshold") {
+                parsed.divan.push(arg);
+                continue;
+            }
+            let value = inline
+                .or_else(|| args.next().map(|value| value.to_string_lossy().into_owned()))
+                .ok_or_else(|| format!("{flag} needs a value"))?;
+            match flag.as_str() {
+                "--threshold" => {
+                    parsed.threshold = value.parse().map_err(|_err| format!("'{value}' is not a percentage"))?;
+                }
+                _ if !lib::baseline::is_valid_name(&value) => {
+                    return Err(format!(
+                        "'{value}' is not a valid baseline name, use letters, digits, '-', '_' and '.'"
+                    ))
+                }
+                "--save-baseline" => parsed.save = Some(value),
+                _ => parsed.compare = Some(value),
+            }
+        }
+        Ok(parsed)
     }
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day04_part2() -> u64 {
-        day4::part2(black_box(INPUT_D4))
+/// Run divan with the remaining arguments in a child process, passing its output through, then save its results as a
+/// baseline and/or compare them with one. Fails if any median got slower than the threshold allows.
+fn baseline(args: &BaselineArgs) -> Result<ExitCode, String> {
+    // Read the baseline up front, to not measure for nothing.
+    let previous = match &args.compare {
+        Some(name) => Some(Baseline::load(&lib::baseline::path(name)).map_err(|err| err.to_string())?),
+        None => None,
+    };
+
+    let exe = std::env::current_exe().map_err(|err| format!("could not find the benchmark binary: {err}"))?;
+    let mut child = Command::new(exe)
+        .args(&args.divan)
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("could not run divan: {err}"))?;
+    let mut output = String::new();
+    for line in BufReader::new(child.stdout.take().expect("piped stdout")).lines() {
+        let line = line.map_err(|err| format!("could not read the output of divan: {err}"))?;
+        println!("{line}");
+        output.push_str(&line);
+        output.push('\n');
+    }
+    let status = child.wait().map_err(|err| format!("could not run divan: {err}"))?;
+    if !status.success() {
+        return Err(format!("divan failed with {status}"));
+    }
+
+    let current = Baseline {
+        version: String::from(env!("GIT_DESCRIBE")),
+        machine: lib::readme::machine(),
+        runs: SAMPLE_COUNT,
+        benchmarks: lib::baseline::parse_divan_output(&output),
+    };
+    if current.benchmarks.is_empty() {
+        return Err(String::from("divan reported no benchmarks"));
+    }
+    if let Some(name) = &args.save {
+        let path = lib::baseline::path(name);
+        current.save(&path).map_err(|err| err.to_string())?;
+        eprintln!(
+            "Saved {} benchmarks as baseline '{name}' in {}",
+            current.benchmarks.len(),
+            path.display()
+        );
+    }
+
+    let (Some(name), Some(previous)) = (&args.compare, previous) else {
+        return Ok(ExitCode::SUCCESS);
+    };
+    let changes = lib::baseline::compare(&previous, &current, args.threshold);
+    print!("\n{}", lib::baseline::report(name, &previous, &changes, args.threshold));
+    match changes.iter().any(|change| change.verdict == Verdict::Slower) {
+        true => Ok(ExitCode::FAILURE),
+        false => Ok(ExitCode::SUCCESS),
     }
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day05_part1() -> Option<i64> {
-        day5::part1(black_box(INPUT_D5))
-    }
+/// One registered solution run against one of its inputs.
+#[derive(Debug, Clone, Copy)]
+struct SolveCase {
+    solution: &'static Solution,
+    input: InputId,
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day05_part2() -> Option<i64> {
-        day5::part2(black_box(INPUT_D5))
+impl Display for SolveCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day{:02}_{}", self.solution.day, self.solution.name)?;
+        write_variant(f, self.input)
     }
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day06_part1() -> u32 {
-        day6::part1(black_box(INPUT_D6))
-    }
+/// The parse function of a day run against one of its inputs.
+#[derive(Debug, Clone, Copy)]
+struct ParseCase {
+    day: &'static Day,
+    input: InputId,
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day06_part2() -> u32 {
-        day6::part2(black_box(INPUT_D6))
+impl Display for ParseCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day{:02}", self.day.day)?;
+        write_variant(f, self.input)
     }
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day07_part1() -> u64 {
-        day7::part1(black_box(INPUT_D7))
+fn write_variant(f: &mut std::fmt::Formatter<'_>, input: InputId) -> std::fmt::Result {
+    match input.variant {
+        Some(variant) => write!(f, "/{variant}"),
+        None => Ok(()),
     }
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day07_part2() -> u64 {
-        day7::part2(black_box(INPUT_D7))
-    }
+fn solve_cases(test: bool) -> impl Iterator<Item = SolveCase> {
+    lib::registry::solutions().flat_map(move |solution| {
+        solution
+            .inputs
+            .iter()
+            .filter(move |input| input.is_test() == test)
+            .map(move |&input| SolveCase { solution, input })
+    })
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE / 2)]
-    fn day08_part1() -> u64 {
-        day8::part1(black_box(INPUT_D8))
-    }
+fn parse_cases(test: bool) -> impl Iterator<Item = ParseCase> {
+    lib::days::DAYS.iter().flat_map(move |day| {
+        day.inputs()
+            .into_iter()
+            .filter(move |input| input.is_test() == test)
+            .map(move |input| ParseCase { day, input })
+    })
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE / 2)]
-    fn day08_part2() -> u64 {
-        day8::part2(black_box(INPUT_D8))
+/// Benchmark `f` against `input`. Skipped if the input is not available.
+fn bench_input<R>(bencher: Bencher<'_, '_>, input: InputId, f: fn(&str) -> R) {
+    let inputs = Inputs::from_env();
+    match inputs.load(input) {
+        Ok(input) => bencher.bench(|| f(divan::black_box(&input))),
+        Err(err) => eprintln!(
+            "Skipping, input '{}' not available: {err}",
+            inputs.path(input).display()
+        ),
     }
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day09_part1() -> i64 {
-        day9::part1(black_box(INPUT_D9))
-    }
+/// The real puzzle inputs.
+mod real {
+    use super::{bench_input, parse_cases, solve_cases, Bencher, ParseCase, SolveCase, SAMPLE_COUNT};
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day09_part2() -> i64 {
-        day9::part2(black_box(INPUT_D9))
+    /// Parse and solve.
+    #[divan::bench(args = solve_cases(false), sample_count = SAMPLE_COUNT)]
+    fn solve(bencher: Bencher<'_, '_>, case: &SolveCase) {
+        bench_input(bencher, case.input, case.solution.solve);
     }
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE / 10)]
-    fn day10_part1() -> u64 {
-        day10::part1(black_box(INPUT_D10))
+    /// Parse only.
+    #[divan::bench(args = parse_cases(false), sample_count = SAMPLE_COUNT)]
+    fn parse(bencher: Bencher<'_, '_>, case: &ParseCase) {
+        bench_input(bencher, case.input, case.day.parse);
     }
+}
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE / 10)]
-    fn day10_part2() -> u64 {
-        day10::part2(black_box(INPUT_D10))
-    }
+/// The example inputs taken from the puzzle descriptions.
+mod example {
+    use super::{bench_input, parse_cases, solve_cases, Bencher, ParseCase, SolveCase, SAMPLE_COUNT};
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day11_part1() -> u64 {
-        day11::part1(black_box(INPUT_D11))
+    /// Parse and solve.
+    #[divan::bench(args = solve_cases(true), sample_count = SAMPLE_COUNT)]
+    fn solve(bencher: Bencher<'_, '_>, case: &SolveCase) {
+        bench_input(bencher, case.input, case.solution.solve);
     }
 
-    #[bench(sample_count = SAMPLE_COUNT, sample_size = SAMPLE_SIZE)]
-    fn day11_part2() -> u64 {
-        day11::part2_expand_999_999(black_box(INPUT_D11))
+    /// Parse only.
+    #[divan::bench(args = parse_cases(true), sample_count = SAMPLE_COUNT)]
+    fn parse(bencher: Bencher<'_, '_>, case: &ParseCase) {
+        bench_input(bencher, case.input, case.day.parse);
     }
 }
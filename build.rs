@@ -0,0 +1,15 @@
+use std::process::Command;
+
+fn main() {
+    // Identifies the build in machine-readable runner output.
+    let describe = Command::new("git")
+        .args(["describe", "--always", "--dirty", "--tags"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map_or_else(|| String::from("unknown"), |describe| describe.trim().to_owned());
+    println!("cargo:rustc-env=GIT_DESCRIBE={describe}");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+}
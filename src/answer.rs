@@ -0,0 +1,96 @@
+use std::{convert::Infallible, fmt::Display, str::FromStr};
+
+/// Answer of a solution, independent of the type the solution function returns.
+///
+/// Answers are rendered and parsed the way they are written in `answers.toml`: integers as numbers, no answer as
+/// `none` and anything else as text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// The solution found no answer, e.g. day 5 part 2 for an input without seeds.
+    None,
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{n}"),
+            Self::Text(text) => f.write_str(text),
+            Self::None => f.write_str("none"),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    /// Parse an answer as written in `answers.toml`. Never fails, anything that is neither an integer nor `none` is
+    /// text.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "none" {
+            return Ok(Self::None);
+        }
+        Ok(s.replace('_', "")
+            .parse::<i128>()
+            .map_or_else(|_| Self::Text(s.to_owned()), Self::Int))
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Self::Int(i128::from(n))
+            }
+        })*
+    };
+}
+
+impl_from_integer!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_owned())
+    }
+}
+
+impl<T: Into<Self>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        answer.map_or(Self::None, Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Answer;
+
+    #[test]
+    fn test_parse_and_display_round_trip() {
+        for (s, answer) in [
+            ("54601", Answer::Int(54_601)),
+            ("-2", Answer::Int(-2)),
+            ("none", Answer::None),
+            ("RFKXZ", Answer::Text(String::from("RFKXZ"))),
+        ] {
+            assert_eq!(s.parse::<Answer>(), Ok(answer.clone()));
+            assert_eq!(answer.to_string(), s);
+        }
+        assert_eq!("54_601".parse::<Answer>(), Ok(Answer::Int(54_601)));
+    }
+
+    #[test]
+    fn test_from_solution_results() {
+        assert_eq!(Answer::from(142_u64), Answer::Int(142));
+        assert_eq!(Answer::from(-3_i64), "-3".parse().unwrap());
+        assert_eq!(Answer::from(Some(35_i64)), Answer::Int(35));
+        assert_eq!(Answer::from(None::<i64>), Answer::None);
+    }
+}
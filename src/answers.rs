@@ -0,0 +1,132 @@
+use std::{collections::BTreeMap, fmt::Display, path::Path};
+
+use crate::answer::Answer;
+
+/// File name of the expected-answers manifest, stored next to the puzzle inputs.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Expected answers, read from a manifest like `res/answers.toml`:
+///
+/// ```toml
+/// [day1.part1]
+/// part1_test = 142
+/// real = 54_601
+/// ```
+///
+/// Answers are keyed by day, part (the name of the solution function) and input name (`real` or the example variant).
+/// Integers are stored as TOML integers, anything else as strings parsed into an [`Answer`].
+#[derive(Debug, Default, Clone)]
+pub struct Answers {
+    expected: BTreeMap<(u8, String, String), Answer>,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    /// A table or value not following the `[dayN.part] input = answer` layout.
+    Layout(String),
+}
+
+impl Display for AnswersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read answers: {err}"),
+            Self::Toml(err) => write!(f, "could not parse answers: {err}"),
+            Self::Layout(msg) => write!(f, "unexpected answers layout: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+impl Answers {
+    /// Read the manifest at `path`. A missing file results in an empty manifest.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file exists but cannot be read or does not follow the expected layout.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match std::fs::read_to_string(path) {
+            Ok(manifest) => Self::parse(&manifest),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(AnswersError::Io(err)),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Fails if `manifest` is not valid TOML or does not follow the `[dayN.part] input = answer` layout.
+    pub fn parse(manifest: &str) -> Result<Self, AnswersError> {
+        let table = manifest.parse::<toml::Table>().map_err(AnswersError::Toml)?;
+        let mut expected = BTreeMap::new();
+        for (day_key, parts) in table {
+            let day = day_key
+                .strip_prefix("day")
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| AnswersError::Layout(format!("'{day_key}' is not a day, expected e.g. 'day7'")))?;
+            let parts = as_table(&day_key, parts)?;
+            for (part, inputs) in parts {
+                let inputs = as_table(&format!("{day_key}.{part}"), inputs)?;
+                for (input, answer) in inputs {
+                    let answer = match answer {
+                        toml::Value::Integer(answer) => Answer::from(answer),
+                        toml::Value::String(answer) => answer.parse().unwrap_or_else(|never| match never {}),
+                        other => {
+                            return Err(AnswersError::Layout(format!(
+                                "answer '{day_key}.{part}.{input}' must be an integer or a string, got '{other}'"
+                            )))
+                        }
+                    };
+                    expected.insert((day, part.clone(), input), answer);
+                }
+            }
+        }
+        Ok(Self { expected })
+    }
+
+    /// The expected answer of `part` (e.g. `"part1"`) of `day`, run against the input named `input`.
+    pub fn get(&self, day: u8, part: &str, input: &str) -> Option<&Answer> {
+        self.expected.get(&(day, part.to_owned(), input.to_owned()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.expected.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expected.is_empty()
+    }
+}
+
+fn as_table(key: &str, value: toml::Value) -> Result<toml::Table, AnswersError> {
+    match value {
+        toml::Value::Table(table) => Ok(table),
+        other => Err(AnswersError::Layout(format!("'{key}' must be a table, got '{other}'"))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Answers;
+    use crate::answer::Answer;
+
+    #[test]
+    fn test_parse() {
+        let answers =
+            Answers::parse("[day1.part1]\npart1_test = 142\nreal = 54_601\n\n[day5.part2]\ntest = \"none\"\n")
+                .expect("valid manifest");
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers.get(1, "part1", "part1_test"), Some(&Answer::Int(142)));
+        assert_eq!(answers.get(1, "part1", "real"), Some(&Answer::Int(54_601)));
+        assert_eq!(answers.get(5, "part2", "test"), Some(&Answer::None));
+        assert_eq!(answers.get(1, "part2", "real"), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_unexpected_layout() {
+        assert!(Answers::parse("[one.part1]\nreal = 1\n").is_err());
+        assert!(Answers::parse("[day1]\npart1 = 1\n").is_err());
+        assert!(Answers::parse("[day1.part1]\nreal = 1.5\n").is_err());
+    }
+}
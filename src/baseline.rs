@@ -0,0 +1,331 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::units::{format_duration, parse_duration};
+
+/// Timings of a set of divan benchmarks, saved under a name to compare later runs against.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// `git describe` of the benchmarked build.
+    pub version: String,
+    /// Description of the machine the benchmarks ran on.
+    pub machine: String,
+    /// Number of samples per benchmark.
+    pub runs: u32,
+    /// Timings keyed by benchmark path, e.g. `real::solve::day05_part2`.
+    pub benchmarks: BTreeMap<String, Sample>,
+}
+
+/// Fastest, median and slowest sample of one benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::struct_field_names)]
+pub struct Sample {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub max_ns: u64,
+}
+
+impl Sample {
+    pub fn new(min: Duration, median: Duration, max: Duration) -> Self {
+        let nanos = |d: Duration| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX);
+        Self {
+            min_ns: nanos(min),
+            median_ns: nanos(median),
+            max_ns: nanos(max),
+        }
+    }
+
+    pub const fn median(&self) -> Duration {
+        Duration::from_nanos(self.median_ns)
+    }
+}
+
+#[derive(Debug)]
+pub enum BaselineError {
+    Io(PathBuf, std::io::Error),
+    Json(PathBuf, serde_json::Error),
+}
+
+impl Display for BaselineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "could not access baseline '{}': {err}", path.display()),
+            Self::Json(path, err) => write!(f, "could not read baseline '{}': {err}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for BaselineError {}
+
+/// Directory the baselines are stored in: `baselines` in `$CARGO_TARGET_DIR`, or in the `target` directory of this
+/// repository if not set.
+pub fn dir() -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("target"), PathBuf::from)
+        .join("baselines")
+}
+
+/// Path of the baseline `name`.
+pub fn path(name: &str) -> PathBuf {
+    dir().join(format!("{name}.json"))
+}
+
+/// Whether `name` can be used as the name of a baseline, i.e. as a file name.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+impl Baseline {
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read or is not a baseline.
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        let json = std::fs::read_to_string(path).map_err(|err| BaselineError::Io(path.to_owned(), err))?;
+        serde_json::from_str(&json).map_err(|err| BaselineError::Json(path.to_owned(), err))
+    }
+
+    /// Write this baseline to `path`, creating its directory if necessary.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), BaselineError> {
+        let io_err = |err| BaselineError::Io(path.to_owned(), err);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        let mut json = serde_json::to_string_pretty(self).expect("baselines are serializable");
+        json.push('\n');
+        std::fs::write(path, json).map_err(io_err)
+    }
+}
+
+/// How the median of a benchmark changed compared to the baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Changed by no more than the threshold.
+    Unchanged,
+    Faster,
+    Slower,
+    /// Not part of the baseline.
+    New,
+}
+
+impl Verdict {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Unchanged => "",
+            Self::Faster => "faster",
+            Self::Slower => "SLOWER",
+            Self::New => "new",
+        }
+    }
+}
+
+/// The change of one benchmark compared to the baseline.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub name: String,
+    pub baseline: Option<Duration>,
+    pub current: Duration,
+    /// Relative change of the median in percent, e.g. `-12.5`. `None` for new benchmarks.
+    pub percent: Option<f64>,
+    pub verdict: Verdict,
+}
+
+/// The timings in `output`, the tree of results printed by divan, keyed by benchmark path, e.g. `real::parse::day05`.
+///
+/// Groups and the allocation counts below each benchmark are skipped.
+pub fn parse_divan_output(output: &str) -> BTreeMap<String, Sample> {
+    const BRANCHES: [&str; 2] = ["├─ ", "╰─ "];
+    let mut path = Vec::new();
+    let mut benchmarks = BTreeMap::new();
+    for line in output.lines() {
+        // Entries look like `│  ├─ day06/test   222.8 ns  │ 3.749 µs  │ 228.8 ns  │ 278.4 ns  │ 100  │ 100`, the
+        // branch moving 3 characters to the right per level.
+        let Some(branch) = BRANCHES.iter().find_map(|branch| line.find(branch)) else {
+            continue;
+        };
+        let mut columns = line[branch + BRANCHES[0].len()..].split('│');
+        let first = columns.next().unwrap_or_default().trim();
+        // Groups have no timings, leaving nothing after their name.
+        let (name, fastest) = first.split_once(' ').unwrap_or((first, ""));
+        path.truncate(line[..branch].chars().count() / 3);
+        path.push(name);
+        let times = [Some(fastest), columns.next(), columns.next()].map(|time| time.and_then(parse_duration));
+        if let [Some(fastest), Some(slowest), Some(median)] = times {
+            benchmarks.insert(path.join("::"), Sample::new(fastest, median, slowest));
+        }
+    }
+    benchmarks
+}
+
+/// Compare the median of every benchmark of `current` with the one in `baseline`.
+/// Changes of more than `threshold` percent in either direction are flagged as faster or slower.
+pub fn compare(baseline: &Baseline, current: &Baseline, threshold: f64) -> Vec<Change> {
+    current
+        .benchmarks
+        .iter()
+        .map(|(name, sample)| {
+            let before = baseline.benchmarks.get(name).map(Sample::median);
+            let percent = before
+                .filter(|before| !before.is_zero())
+                .map(|before| (sample.median().as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0);
+            let verdict = match (before, percent) {
+                (None, _) => Verdict::New,
+                (Some(_), Some(percent)) if percent > threshold => Verdict::Slower,
+                (Some(_), Some(percent)) if percent < -threshold => Verdict::Faster,
+                (Some(_), _) => Verdict::Unchanged,
+            };
+            Change {
+                name: name.clone(),
+                baseline: before,
+                current: sample.median(),
+                percent,
+                verdict,
+            }
+        })
+        .collect()
+}
+
+/// A table of `changes` compared with the baseline `name`, followed by a summary of the changes beyond `threshold`.
+pub fn report(name: &str, baseline: &Baseline, changes: &[Change], threshold: f64) -> String {
+    let mut out = format!(
+        "{:<40}  {:>10}  {:>10}  {:>9}\n",
+        "benchmark", "baseline", "median", "change"
+    );
+    for change in changes {
+        let _ = writeln!(
+            out,
+            "{:<40}  {:>10}  {:>10}  {:>9}  {}",
+            change.name,
+            change.baseline.map_or_else(|| String::from("-"), format_duration),
+            format_duration(change.current),
+            change
+                .percent
+                .map_or_else(|| String::from("-"), |p| format!("{p:+.1} %")),
+            change.verdict.label()
+        );
+    }
+    let count = |verdict: Verdict| changes.iter().filter(|c| c.verdict == verdict).count();
+    let _ = writeln!(
+        out,
+        "\nCompared with baseline '{name}' ({}): {} slower, {} faster, {} new, beyond ±{threshold} %",
+        baseline.version,
+        count(Verdict::Slower),
+        count(Verdict::Faster),
+        count(Verdict::New),
+    );
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compare, is_valid_name, parse_divan_output, report, Baseline, Sample, Verdict};
+    use std::time::Duration;
+
+    fn baseline(medians: &[(&str, u64)]) -> Baseline {
+        Baseline {
+            version: String::from("abc1234"),
+            machine: String::from("Test CPU"),
+            runs: 10,
+            benchmarks: medians
+                .iter()
+                .map(|&(name, micros)| {
+                    let median = Duration::from_micros(micros);
+                    (name.to_owned(), Sample::new(median, median, median))
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_compare_flags_changes_beyond_threshold() {
+        let before = baseline(&[("a", 100), ("b", 100), ("c", 100), ("removed", 1)]);
+        let after = baseline(&[("a", 104), ("b", 120), ("c", 80), ("d", 5)]);
+        let changes = compare(&before, &after, 5.0);
+        let verdicts = changes.iter().map(|c| (c.name.as_str(), c.verdict)).collect::<Vec<_>>();
+        assert_eq!(
+            verdicts,
+            [
+                ("a", Verdict::Unchanged),
+                ("b", Verdict::Slower),
+                ("c", Verdict::Faster),
+                ("d", Verdict::New)
+            ]
+        );
+        assert!((changes[1].percent.expect("percent") - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_divan_output() {
+        let output = "\
+benchmarks              fastest       │ slowest       │ median        │ mean          │ samples │ iters
+├─ example                            │               │               │               │         │
+│  ╰─ parse                           │               │               │               │         │
+│     ├─ day06/test     222.8 ns      │ 3.749 µs      │ 228.8 ns      │ 278.4 ns      │ 100     │ 100
+│     │                 alloc:        │               │               │               │         │
+│     │                   2           │ 2             │ 2             │ 2             │         │
+│     │                   80 B        │ 80 B          │ 80 B          │ 80 B          │         │
+│     ╰─ day10/part2_test1  1.2 µs    │ 2 µs          │ 1.5 µs        │ 1.6 µs        │ 100     │ 100
+╰─ real                               │               │               │               │         │
+   ╰─ solve                           │               │               │               │         │
+      ╰─ day05_part2    106 µs        │ 1.1 ms        │ 114.8 µs      │ 127.1 µs      │ 100     │ 100
+";
+        let benchmarks = parse_divan_output(output);
+        assert_eq!(
+            benchmarks.keys().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "example::parse::day06/test",
+                "example::parse::day10/part2_test1",
+                "real::solve::day05_part2"
+            ]
+        );
+        assert_eq!(
+            benchmarks["real::solve::day05_part2"],
+            Sample::new(
+                Duration::from_micros(106),
+                Duration::from_nanos(114_800),
+                Duration::from_micros(1_100)
+            )
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let before = baseline(&[("a", 100)]);
+        let changes = compare(&before, &baseline(&[("a", 120)]), 5.0);
+        let report = report("main", &before, &changes, 5.0);
+        assert!(report.contains("100.0 μs    120.0 μs    +20.0 %  SLOWER\n"));
+        assert!(report.ends_with("Compared with baseline 'main' (abc1234): 1 slower, 0 faster, 0 new, beyond ±5 %\n"));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("aoc23-baseline-{}.json", std::process::id()));
+        let saved = baseline(&[("real::solve::day05_part2", 60)]);
+        saved.save(&path).expect("saved");
+        let loaded = Baseline::load(&path).expect("loaded");
+        std::fs::remove_file(&path).expect("removed");
+        assert_eq!(loaded, saved);
+    }
+
+    #[test]
+    fn test_valid_names() {
+        assert!(is_valid_name("main"));
+        assert!(is_valid_name("before-simd_2.1"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("../main"));
+        assert!(!is_valid_name(".hidden"));
+    }
+}
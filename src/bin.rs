@@ -1,145 +1,1090 @@
-use lib::days::*;
-
-const INPUT_D1_PART1_TEST: &str = include_str!("../res/day1_part1_test.txt");
-const INPUT_D1_PART2_TEST: &str = include_str!("../res/day1_part2_test.txt");
-const INPUT_D2_TEST: &str = include_str!("../res/day2_test.txt");
-const INPUT_D3_TEST: &str = include_str!("../res/day3_test.txt");
-const INPUT_D4_TEST: &str = include_str!("../res/day4_test.txt");
-const INPUT_D5_TEST: &str = include_str!("../res/day5_test.txt");
-const INPUT_D6_TEST: &str = include_str!("../res/day6_test.txt");
-const INPUT_D7_TEST: &str = include_str!("../res/day7_test.txt");
-const INPUT_D7_TEST2: &str = include_str!("../res/day7_test2.txt");
-const INPUT_D8_PART1_TEST: &str = include_str!("../res/day8_part1_test.txt");
-const INPUT_D8_PART1_TEST2: &str = include_str!("../res/day8_part1_test2.txt");
-const INPUT_D8_PART2_TEST: &str = include_str!("../res/day8_part2_test.txt");
-const INPUT_D9_TEST: &str = include_str!("../res/day9_test.txt");
-const INPUT_D10_PART1_TEST1: &str = include_str!("../res/day10_part1_test1.txt");
-const INPUT_D10_PART1_TEST2: &str = include_str!("../res/day10_part1_test2.txt");
-const INPUT_D10_PART2_TEST1: &str = include_str!("../res/day10_part2_test1.txt");
-const INPUT_D10_PART2_TEST2: &str = include_str!("../res/day10_part2_test2.txt");
-const INPUT_D10_PART2_TEST3: &str = include_str!("../res/day10_part2_test3.txt");
-const INPUT_D11_TEST: &str = include_str!("../res/day11_test.txt");
-const INPUT_D12_PART1_TEST1: &str = include_str!("../res/day12_part1_test1.txt");
-
-const INPUT_D1: &str = include_str!("../res/day1.txt");
-const INPUT_D2: &str = include_str!("../res/day2.txt");
-const INPUT_D3: &str = include_str!("../res/day3.txt");
-const INPUT_D4: &str = include_str!("../res/day4.txt");
-const INPUT_D5: &str = include_str!("../res/day5.txt");
-const INPUT_D6: &str = include_str!("../res/day6.txt");
-const INPUT_D7: &str = include_str!("../res/day7.txt");
-const INPUT_D8: &str = include_str!("../res/day8.txt");
-const INPUT_D9: &str = include_str!("../res/day9.txt");
-const INPUT_D10: &str = include_str!("../res/day10.txt");
-const INPUT_D11: &str = include_str!("../res/day11.txt");
-const INPUT_D12: &str = include_str!("../res/day12.txt");
-
-fn main() {
-    init_tracing(tracing::level_filters::LevelFilter::INFO);
-
-    run(day1::part1, INPUT_D1_PART1_TEST, 142);
-    run(day1::part2, INPUT_D1_PART2_TEST, 281);
-    run(day1::part1, INPUT_D1, 54_601);
-    run(day1::part2, INPUT_D1, 54_078);
-
-    run(day2::part1, INPUT_D2_TEST, 8);
-    run(day2::part2, INPUT_D2_TEST, 2_286);
-    run(day2::part1, INPUT_D2, 2_317);
-    run(day2::part2, INPUT_D2, 74_804);
-
-    run(day3::part1, INPUT_D3_TEST, 4_361);
-    run(day3::part2, INPUT_D3_TEST, 467_835);
-    run(day3::part1, INPUT_D3, 556_367);
-    run(day3::part2, INPUT_D3, 89_471_771);
-
-    run(day4::part1, INPUT_D4_TEST, 13);
-    run(day4::part2, INPUT_D4_TEST, 230);
-    run(day4::part1, INPUT_D4, 23_028);
-    run(day4::part2, INPUT_D4, 9_236_992);
-
-    run(day5::part1, INPUT_D5_TEST, Some(35));
-    run(day5::part2, INPUT_D5_TEST, Some(46));
-    run(day5::part1, INPUT_D5, Some(403_695_602));
-    run(day5::part2, INPUT_D5, Some(219_529_182));
-
-    run(day6::part1, INPUT_D6_TEST, 288);
-    run(day6::part2, INPUT_D6_TEST, 71_503);
-    run(day6::part1, INPUT_D6, 211_904);
-    run(day6::part2, INPUT_D6, 43_364_472);
-
-    run(day7::part1, INPUT_D7_TEST, 6_440);
-    run(day7::part1, INPUT_D7_TEST2, 6_592);
-    run(day7::part2, INPUT_D7_TEST, 5_905);
-    run(day7::part2, INPUT_D7_TEST2, 6_839);
-    run(day7::part1, INPUT_D7, 253_205_868);
-    run(day7::part2, INPUT_D7, 253_907_829);
-
-    run(day8::part1, INPUT_D8_PART1_TEST, 2);
-    run(day8::part1, INPUT_D8_PART1_TEST2, 6);
-    run(day8::part1, INPUT_D8, 18_157);
-    run(day8::part2, INPUT_D8_PART2_TEST, 6);
-    run(day8::part2, INPUT_D8, 14_299_763_833_181);
-
-    run(day9::part1, INPUT_D9_TEST, 114);
-    run(day9::part1, INPUT_D9, 1_877_825_184);
-    run(day9::part2, INPUT_D9_TEST, 2);
-    run(day9::part2, INPUT_D9, 1_108);
-
-    run(day10::part1, INPUT_D10_PART1_TEST1, 4);
-    run(day10::part1, INPUT_D10_PART1_TEST2, 8);
-    run(day10::part1, INPUT_D10, 6_875);
-    run(day10::part2, INPUT_D10_PART1_TEST1, 1);
-    run(day10::part2, INPUT_D10_PART1_TEST2, 1);
-    run(day10::part2, INPUT_D10_PART2_TEST1, 4);
-    run(day10::part2, INPUT_D10_PART2_TEST2, 8);
-    run(day10::part2, INPUT_D10_PART2_TEST3, 10);
-    run(day10::part2, INPUT_D10, 471);
-
-    run(day11::part1, INPUT_D11_TEST, 374);
-    run(day11::part1, INPUT_D11, 9_627_977);
-    run(day11::part2_expand_9, INPUT_D11_TEST, 1_030);
-    run(day11::part2_expand_99, INPUT_D11_TEST, 8_410);
-    run(day11::part2_expand_999_999, INPUT_D11_TEST, 82_000_210);
-    run(day11::part2_expand_999_999, INPUT_D11, 644_248_339_497);
-
-    run(day12::part1, INPUT_D12_PART1_TEST1, 21);
-    run(day12::part1, INPUT_D12, 7_195);
-    run(day12::part2, INPUT_D12_PART1_TEST1, 525152);
-    run(day12::part2, INPUT_D12, 33992866292225);
-}
-
-#[tracing::instrument(level = "INFO", skip_all, fields(name = std::any::type_name::<F>()))]
-fn run<R: std::fmt::Debug + PartialEq, F: Fn(&str) -> R>(fun: F, input: &str, expect: R) {
-    let start = std::time::Instant::now();
-    let result = fun(input);
-    let end = std::time::Instant::now();
-    let took = (end - start).as_micros();
-    tracing::info!(?result, took = format!("{took} μs"));
-    assert_eq!(result, expect);
-}
-
-pub fn init_tracing(level: tracing::level_filters::LevelFilter) {
-    use tracing_subscriber::filter::Targets;
-    use tracing_subscriber::fmt::format::{Format, Pretty};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+    io::IsTerminal,
+    ops::RangeInclusive,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use lib::answer::Answer;
+use lib::answers::{Answers, ANSWERS_FILE};
+use lib::days::DAYS;
+use lib::inputs::{InputId, Inputs};
+use lib::memory::AllocStats;
+use lib::parse::ParseError;
+use lib::registry::{Solution, Timed};
+use lib::units::{format_bytes, format_duration};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::Serialize;
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOC: lib::memory::CountingAllocator = lib::memory::CountingAllocator;
+
+/// Run the Advent of Code 2023 solutions.
+#[derive(Debug, Parser)]
+#[command(
+    name = "bin",
+    args_conflicts_with_subcommands = true,
+    after_help = after_help(),
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    run: RunArgs,
+
+    /// Log filter, e.g. `debug` or `warn,lib::days::day5=trace`. Defaults to `$RUST_LOG`, or `info` if not set.
+    #[arg(long, global = true, value_name = "FILTER")]
+    log: Option<String>,
+
+    /// Write all spans, including those inside the solvers, to this file in the Chrome trace format.
+    /// Open it in `chrome://tracing` or <https://ui.perfetto.dev>.
+    #[arg(long, global = true, value_name = "PATH")]
+    trace_out: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run the selected solutions, check every answer against `answers.toml` and print a pass/fail report.
+    /// Exits with a non-zero code if any answer is wrong or any solution panicked.
+    Verify(RunArgs),
+    /// Run every solution against its real input and rewrite the benchmark table in `README.md`, showing the change
+    /// of each median compared to the previous table.
+    Readme(ReadmeArgs),
+    /// Generate a random but valid input for a day, e.g. to test or benchmark the solutions with larger inputs.
+    #[command(after_help = generators_help())]
+    Generate(GenerateArgs),
+    /// Add a new day: create its module with placeholder solutions and tests, placeholder inputs in `res`, and register
+    /// it with the runner, the benchmarks, `answers.toml` and the input generators.
+    NewDay(NewDayArgs),
+}
+
+#[derive(Debug, Args)]
+struct ReadmeArgs {
+    /// Number of runs per solution.
+    #[arg(short, long, value_name = "N", default_value_t = 100, value_parser = clap::value_parser!(u32).range(1..))]
+    repeat: u32,
+
+    /// Directory to read the puzzle inputs from. Defaults to `$AOC_INPUT_DIR` or the `res` directory of this repository.
+    #[arg(long, value_name = "DIR")]
+    input_dir: Option<PathBuf>,
+
+    /// The README to update. It must contain the benchmark table markers.
+    #[arg(long, value_name = "PATH", default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/README.md"))]
+    readme: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct GenerateArgs {
+    /// Day to generate an input for.
+    day: u8,
+
+    /// Seed of the random number generator. The same seed, size and density always produce the same input.
+    #[arg(short, long, default_value_t = 0)]
+    seed: u64,
+
+    /// Size of the input, see below for what it controls. Defaults to a size similar to the real input.
+    #[arg(long, value_name = "N")]
+    size: Option<usize>,
+
+    /// Density between 0 and 1, see below for what it controls. Ignored by days without a density.
+    #[arg(long, value_name = "P")]
+    density: Option<f64>,
+
+    /// Write the input to this file instead of stdout.
+    #[arg(short, long, value_name = "PATH")]
+    out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct NewDayArgs {
+    /// The day to add.
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+
+    /// Title of the puzzle, e.g. `"Point of Incidence"`. Defaults to `Day N`.
+    #[arg(short, long)]
+    title: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct RunArgs {
+    /// Days to run. Either a single day (`7`) or a range of days (`5..=9`, `5..10`). Runs all days if omitted.
+    /// Use `<DAY> [<PART>] -` to solve the input read from stdin, printing only the answer.
+    #[arg(value_parser = parse_selector, conflicts_with = "all")]
+    days: Vec<Selector>,
+
+    /// Only run the given part of each selected day.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Run all available days.
+    #[arg(short, long)]
+    all: bool,
+
+    /// Only run the example inputs taken from the puzzle descriptions, skipping the real puzzle inputs.
+    #[arg(short, long, conflicts_with = "input")]
+    test_inputs_only: bool,
+
+    /// Directory to read the puzzle inputs and their `answers.toml` from.
+    /// Defaults to `$AOC_INPUT_DIR` or the `res` directory of this repository.
+    #[arg(long, value_name = "DIR")]
+    input_dir: Option<PathBuf>,
+
+    /// Run the selected days and parts against this file instead of the known inputs. Answers are not checked.
+    #[arg(short, long, value_name = "PATH", requires = "days")]
+    input: Option<PathBuf>,
+
+    /// Number of threads to run solutions on. Defaults to the number of CPUs.
+    #[arg(short, long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..), conflicts_with = "sequential")]
+    jobs: Option<u16>,
+
+    /// Run all solutions one after another on the main thread, for the most accurate timings.
+    #[arg(long)]
+    sequential: bool,
+
+    /// Run each solution N times, reporting min, median and max of the parse, solve and total times.
+    #[arg(short, long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    repeat: u32,
+
+    /// How results are written to stdout.
+    #[arg(long, value_enum, default_value_t = Format::Pretty)]
+    format: Format,
+
+    /// Before solving, draw each input of the selected days as an annotated grid, for the days supporting it (3, 10
+    /// and 11). Colored unless stdout is not a terminal or `NO_COLOR` is set.
+    #[arg(long)]
+    visualize: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Human-readable log output. `verify` additionally prints a report table.
+    Pretty,
+    /// One JSON object per executed solution and input (JSON Lines). Logs are still written to stderr.
+    Json,
+}
+
+fn after_help() -> String {
+    let mut help = String::from("Available days:\n");
+    for day in DAYS {
+        let parts = day.solutions.iter().map(|s| s.name).collect::<Vec<_>>().join(", ");
+        let _ = writeln!(help, "  {:>2}  {:<32}  {parts}", day.day, day.title);
+    }
+    help.push_str("\nExamples:\n  bin 7\n  bin 7 --part 2\n  bin 5..=9\n  bin --all --test-inputs-only\n  bin 10 --test-inputs-only --visualize\n  bin verify\n  cat input.txt | bin 12 2 -");
+    help
+}
+
+fn generators_help() -> String {
+    let mut help = String::from("Sizes and densities per day:\n");
+    for generator in lib::generate::GENERATORS {
+        let _ = write!(
+            help,
+            "  {:>2}  size: {} ({})",
+            generator.day, generator.size, generator.default_size
+        );
+        if let Some(density) = generator.density {
+            let _ = write!(help, ", density: {density} ({})", generator.default_density);
+        }
+        help.push('\n');
+    }
+    help.push_str("\nExamples:\n  bin generate 10 --seed 42 --size 500\n  bin generate 12 --density 0.8 | bin 12 -");
+    help
+}
+
+/// A positional argument: days to run, or `-` to read the input from stdin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Selector {
+    Days(RangeInclusive<u8>),
+    Stdin,
+}
+
+impl Selector {
+    const fn days(self) -> Option<RangeInclusive<u8>> {
+        match self {
+            Self::Days(days) => Some(days),
+            Self::Stdin => None,
+        }
+    }
+
+    /// The number given, if this selects a single day.
+    fn single(&self) -> Option<u8> {
+        match self {
+            Self::Days(days) if days.start() == days.end() => Some(*days.start()),
+            _ => None,
+        }
+    }
+}
+
+fn parse_selector(s: &str) -> Result<Selector, String> {
+    match s {
+        "-" => Ok(Selector::Stdin),
+        days => parse_days(days).map(Selector::Days),
+    }
+}
+
+/// Parse a day selector, being either a single day (`7`), an inclusive (`5..=9`) or an exclusive (`5..10`) range.
+fn parse_days(s: &str) -> Result<RangeInclusive<u8>, String> {
+    fn parse_num(s: &str) -> Result<u8, String> {
+        s.trim()
+            .parse::<u8>()
+            .map_err(|err| format!("'{s}' is not a valid day: {err}"))
+    }
+
+    let days = if let Some((start, end)) = s.split_once("..=") {
+        parse_num(start)?..=parse_num(end)?
+    } else if let Some((start, end)) = s.split_once("..") {
+        parse_num(start)?..=parse_num(end)?.saturating_sub(1)
+    } else {
+        let day = parse_num(s)?;
+        day..=day
+    };
+    if days.is_empty() {
+        return Err(format!("day range '{s}' is empty"));
+    }
+    for day in [*days.start(), *days.end()] {
+        if lib::registry::day(day).is_none() {
+            let last = DAYS.last().map_or(0, |d| d.day);
+            return Err(format!("day {day} is not available, expected a day in 1..={last}"));
+        }
+    }
+    Ok(days)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Fail,
+    /// No expected answer is known for this combination of day, part and input.
+    MissingExpectation,
+    Panicked(String),
+    /// The input could not be parsed.
+    Invalid(ParseError),
+}
+
+impl Status {
+    const fn is_failure(&self) -> bool {
+        matches!(self, Self::Fail | Self::Panicked(_) | Self::Invalid(_))
+    }
+
+    /// Machine-readable name of the status, used in JSON output.
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Fail => "fail",
+            Self::MissingExpectation => "missing",
+            Self::Panicked(_) => "panicked",
+            Self::Invalid(_) => "invalid",
+        }
+    }
+
+    const fn label(&self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Fail => "FAIL",
+            Self::MissingExpectation => "missing",
+            Self::Panicked(_) => "PANIC",
+            Self::Invalid(_) => "INVALID",
+        }
+    }
+}
+
+/// Minimum, median and maximum of repeated time measurements.
+#[derive(Debug, Clone, Copy)]
+struct Stats {
+    min: Duration,
+    median: Duration,
+    max: Duration,
+}
+
+impl Stats {
+    fn of(samples: impl Iterator<Item = Duration>) -> Self {
+        let mut samples = samples.collect::<Vec<_>>();
+        samples.sort_unstable();
+        Self {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.min == self.max {
+            true => f.write_str(&format_duration(self.median)),
+            false => write!(
+                f,
+                "{} (min {}, max {})",
+                format_duration(self.median),
+                format_duration(self.min),
+                format_duration(self.max)
+            ),
+        }
+    }
+}
+
+/// Time spent in the parse and solve steps over all runs of a solution.
+#[derive(Debug, Clone, Copy)]
+struct Timings {
+    runs: usize,
+    parse: Stats,
+    solve: Stats,
+    total: Stats,
+}
+
+impl Timings {
+    /// `None` if there were no successful runs.
+    fn of(runs: &[Timed]) -> Option<Self> {
+        if runs.is_empty() {
+            return None;
+        }
+        Some(Self {
+            runs: runs.len(),
+            parse: Stats::of(runs.iter().map(|run| run.parse)),
+            solve: Stats::of(runs.iter().map(|run| run.solve)),
+            total: Stats::of(runs.iter().map(Timed::total)),
+        })
+    }
+}
+
+/// Result of running one solution against one input.
+#[derive(Debug)]
+struct Outcome {
+    solution: &'static Solution,
+    input: String,
+    answer: Option<Answer>,
+    expected: Option<Answer>,
+    status: Status,
+    timings: Option<Timings>,
+    /// Heap usage of the first run. Only measured with the `count-allocations` feature.
+    memory: Option<AllocStats>,
+}
+
+/// Decides which of the registered solutions are actually run and provides their inputs.
+#[derive(Debug)]
+struct Runner {
+    days: Vec<RangeInclusive<u8>>,
+    part: Option<u8>,
+    test_inputs_only: bool,
+    inputs: Inputs,
+    answers: Answers,
+    /// Input given through `--input`, replacing all registered inputs.
+    explicit_input: Option<String>,
+    /// Thread pool to run solutions on. `None` when running sequentially.
+    pool: Option<rayon::ThreadPool>,
+    repeat: u32,
+}
+
+/// One solution to run against one input.
+#[derive(Debug)]
+struct Job<'a> {
+    solution: &'static Solution,
+    input: &'a str,
+    input_name: String,
+    expected: Option<&'a Answer>,
+    repeat: u32,
+}
+
+impl Runner {
+    fn new(args: RunArgs) -> Result<Self, String> {
+        let explicit_input = match args.input {
+            Some(path) => Some(
+                std::fs::read_to_string(&path)
+                    .map_err(|err| format!("could not read input '{}': {err}", path.display()))?,
+            ),
+            None => None,
+        };
+        let inputs = args.input_dir.map_or_else(Inputs::from_env, Inputs::new);
+        let answers_path = inputs.dir().join(ANSWERS_FILE);
+        let answers = Answers::load(&answers_path).map_err(|err| format!("{}: {err}", answers_path.display()))?;
+        if answers.is_empty() {
+            tracing::warn!(path = %answers_path.display(), "No expected answers available");
+        }
+        let pool = match args.sequential {
+            true => None,
+            false => Some(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(args.jobs.map_or(0, usize::from))
+                    .build()
+                    .map_err(|err| format!("could not start thread pool: {err}"))?,
+            ),
+        };
+        Ok(Self {
+            days: match args.all {
+                true => Vec::new(),
+                false => args.days.into_iter().filter_map(Selector::days).collect(),
+            },
+            part: args.part,
+            test_inputs_only: args.test_inputs_only,
+            inputs,
+            answers,
+            explicit_input,
+            pool,
+            repeat: args.repeat,
+        })
+    }
+
+    fn is_selected(&self, solution: &Solution) -> bool {
+        (self.days.is_empty() || self.days.iter().any(|days| days.contains(&solution.day)))
+            && self.part.is_none_or(|p| p == solution.part)
+    }
+
+    /// Run every selected solution against each of its inputs, or against the explicit input if one was given.
+    /// Outcomes are ordered by day, part and input, regardless of whether the solutions ran in parallel.
+    fn run_all(&self) -> Vec<Outcome> {
+        let selected = lib::registry::solutions()
+            .filter(|s| self.is_selected(s))
+            .collect::<Vec<_>>();
+        let ids = |solution: &'static Solution| {
+            solution
+                .inputs
+                .iter()
+                .copied()
+                .filter(|id| !self.test_inputs_only || id.is_test())
+        };
+
+        // Inputs are read up front, so that jobs only borrow them. `None` if the input file is missing.
+        let mut loaded: HashMap<InputId, Option<String>> = HashMap::new();
+        if self.explicit_input.is_none() {
+            for id in selected.iter().flat_map(|s| ids(s)) {
+                loaded.entry(id).or_insert_with(|| match self.inputs.load(id) {
+                    Ok(input) => Some(input),
+                    Err(err) => {
+                        tracing::warn!(
+                            day = id.day,
+                            input = %id,
+                            path = %self.inputs.path(id).display(),
+                            %err,
+                            "Skipping, input not available"
+                        );
+                        None
+                    }
+                });
+            }
+        }
+
+        let mut jobs = Vec::new();
+        for solution in selected {
+            if let Some(explicit_input) = &self.explicit_input {
+                jobs.push(Job {
+                    solution,
+                    input: explicit_input,
+                    input_name: String::from("explicit"),
+                    expected: None,
+                    repeat: self.repeat,
+                });
+                continue;
+            }
+            for id in ids(solution) {
+                if let Some(Some(input)) = loaded.get(&id) {
+                    jobs.push(Job {
+                        solution,
+                        input,
+                        input_name: id.to_string(),
+                        expected: self.answers.get(solution.day, solution.name, &id.to_string()),
+                        repeat: self.repeat,
+                    });
+                }
+            }
+        }
+
+        match &self.pool {
+            None => jobs.into_iter().map(run).collect(),
+            Some(pool) => pool.install(|| jobs.into_par_iter().map(run).collect()),
+        }
+    }
+
+    /// Print every input of the selected days as an annotated grid, or the explicit input if one was given. Days
+    /// without a visualization are skipped.
+    fn visualize_all(&self, colors: bool) {
+        let selected = DAYS
+            .iter()
+            .filter(|day| self.days.is_empty() || self.days.iter().any(|days| days.contains(&day.day)));
+        for day in selected {
+            let Some(visualize) = day.visualize else {
+                if !self.days.is_empty() {
+                    tracing::warn!(day = day.day, "No visualization available");
+                }
+                continue;
+            };
+            let inputs = match &self.explicit_input {
+                Some(input) => vec![(String::from("explicit"), input.clone())],
+                // Missing inputs are reported when running the solutions.
+                None => day
+                    .inputs()
+                    .into_iter()
+                    .filter(|id| !self.test_inputs_only || id.is_test())
+                    .filter_map(|id| self.inputs.load(id).ok().map(|input| (id.to_string(), input)))
+                    .collect(),
+            };
+            for (name, input) in inputs {
+                match visualize(&input) {
+                    Ok(canvas) => println!(
+                        "Day {} \"{}\", input {name}:\n{}",
+                        day.day,
+                        day.title,
+                        canvas.render(colors)
+                    ),
+                    Err(err) => tracing::error!(day = day.day, input = name, "Invalid input: {err}"),
+                }
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // Keeps the trace file open. Dropped, and thereby flushed, when returning from `main`.
+    let _trace_guard = match init_tracing(cli.log.as_deref(), cli.trace_out.as_deref()) {
+        Ok(guard) => guard,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let (args, verify) = match cli.command {
+        Some(Command::Verify(args)) => (args, true),
+        Some(Command::Readme(args)) => match update_readme(args) {
+            Ok(()) => return ExitCode::SUCCESS,
+            Err(err) => {
+                tracing::error!("{err}");
+                return ExitCode::from(2);
+            }
+        },
+        Some(Command::Generate(args)) => match generate(args) {
+            Ok(()) => return ExitCode::SUCCESS,
+            Err(err) => {
+                tracing::error!("{err}");
+                return ExitCode::from(2);
+            }
+        },
+        Some(Command::NewDay(args)) => match new_day(&args) {
+            Ok(()) => return ExitCode::SUCCESS,
+            Err(err) => {
+                tracing::error!("{err}");
+                return ExitCode::from(2);
+            }
+        },
+        None => (cli.run, false),
+    };
+    match stdin_selection(&args.days, args.part) {
+        Ok(None) => {}
+        Ok(Some(_)) if verify => {
+            tracing::error!("verify does not support reading the input from stdin");
+            return ExitCode::from(2);
+        }
+        Ok(Some((day, part))) => match solve_stdin(day, part) {
+            Ok(()) => return ExitCode::SUCCESS,
+            Err(err) => {
+                tracing::error!("{err}");
+                return ExitCode::from(2);
+            }
+        },
+        Err(err) => {
+            tracing::error!("{err}");
+            return ExitCode::from(2);
+        }
+    }
+
+    let format = args.format;
+    let visualize = args.visualize;
+    let runner = match Runner::new(args) {
+        Ok(runner) => runner,
+        Err(err) => {
+            tracing::error!("{err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if visualize {
+        let colors = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        runner.visualize_all(colors);
+    }
+
+    if verify {
+        // Panics are reported in the verification report.
+        std::panic::set_hook(Box::new(|_info| {}));
+    }
+    let outcomes = runner.run_all();
+    let _ = std::panic::take_hook();
+
+    match format {
+        Format::Json => print_json_lines(&outcomes),
+        Format::Pretty if verify => print_report(&outcomes),
+        Format::Pretty => {}
+    }
+    match outcomes.iter().any(|outcome| outcome.status.is_failure()) {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+/// The day and optional part to solve for the input read from stdin, if the selectors end with `-`.
+fn stdin_selection(selectors: &[Selector], part: Option<u8>) -> Result<Option<(u8, Option<u8>)>, String> {
+    let selection = match selectors {
+        [day, Selector::Stdin] => day.single().map(|day| (day, part)),
+        [day, part, Selector::Stdin] => day
+            .single()
+            .zip(part.single().filter(|part| (1..=2).contains(part)))
+            .map(|(day, part)| (day, Some(part))),
+        _ if selectors.contains(&Selector::Stdin) => None,
+        _ => return Ok(None),
+    };
+    selection
+        .map(Some)
+        .ok_or_else(|| String::from("reading the input from stdin expects `<DAY> [<PART>] -`, e.g. `bin 12 2 -`"))
+}
+
+/// Solve `day`, or only its `part`, for the input read from stdin, printing one answer per line.
+fn solve_stdin(day: u8, part: Option<u8>) -> Result<(), String> {
+    let mut input = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)
+        .map_err(|err| format!("could not read input from stdin: {err}"))?;
+    let day = lib::registry::day(day).ok_or_else(|| format!("day {day} is not available"))?;
+    for part in [1, 2].into_iter().filter(|p| part.is_none_or(|part| part == *p)) {
+        // A part may be solved by several solutions, e.g. with different parameters for the examples.
+        // Prefer the one registered for the real input.
+        let mut solutions = day.solutions.iter().filter(|s| s.part == part);
+        let solution = solutions
+            .clone()
+            .find(|s| s.inputs.iter().any(|id| !id.is_test()))
+            .or_else(|| solutions.next());
+        if let Some(solution) = solution {
+            let answer = (solution.solve)(&input).map_err(|err| format!("invalid input: {err}"))?;
+            println!("{answer}");
+        }
+    }
+    Ok(())
+}
+
+/// Run every solution against each of its inputs, sequentially for the most accurate timings, and replace the
+/// benchmark table of the README with the results.
+fn update_readme(args: ReadmeArgs) -> Result<(), String> {
+    use lib::readme::{BenchInfo, BenchRow, BEGIN_MARKER, END_MARKER};
+
+    let path = &args.readme;
+    let readme = std::fs::read_to_string(path).map_err(|err| format!("could not read '{}': {err}", path.display()))?;
+    let info = BenchInfo {
+        machine: lib::readme::machine(),
+        version: String::from(env!("GIT_DESCRIBE")),
+        runs: args.repeat,
+    };
+    let previous = lib::readme::region(&readme)
+        .map(|table| lib::readme::previous_medians(table, &info.machine))
+        .ok_or_else(|| format!("'{}' has no {BEGIN_MARKER} ... {END_MARKER} region", path.display()))?;
+
+    let runner = Runner::new(RunArgs {
+        days: Vec::new(),
+        part: None,
+        all: true,
+        test_inputs_only: false,
+        input_dir: args.input_dir,
+        input: None,
+        jobs: None,
+        sequential: true,
+        repeat: args.repeat,
+        format: Format::Pretty,
+        visualize: false,
+    })?;
+    let rows = runner
+        .run_all()
+        .into_iter()
+        .filter_map(|outcome| {
+            outcome.timings.map(|t| BenchRow {
+                day: outcome.solution.day,
+                name: outcome.solution.name,
+                input: outcome.input,
+                min: t.total.min,
+                median: t.total.median,
+                max: t.total.max,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let table = lib::readme::render(&info, &rows, &previous);
+    let updated = lib::readme::replace_region(&readme, &table).expect("region was found before");
+    std::fs::write(path, updated).map_err(|err| format!("could not write '{}': {err}", path.display()))?;
+    tracing::info!(path = %path.display(), rows = rows.len(), "Updated benchmark table");
+    Ok(())
+}
+
+/// Generate an input for the requested day and write it to stdout or the requested file.
+fn generate(args: GenerateArgs) -> Result<(), String> {
+    let generator =
+        lib::generate::generator(args.day).ok_or_else(|| format!("no input generator for day {}", args.day))?;
+    let input = generator.generate(args.seed, args.size.unwrap_or(generator.default_size), args.density);
+    match args.out {
+        Some(path) => {
+            std::fs::write(&path, input).map_err(|err| format!("could not write '{}': {err}", path.display()))
+        }
+        None => {
+            print!("{input}");
+            Ok(())
+        }
+    }
+}
+
+fn new_day(args: &NewDayArgs) -> Result<(), lib::scaffold::ScaffoldError> {
+    use lib::scaffold::Change;
+
+    let title = args.title.clone().unwrap_or_else(|| format!("Day {}", args.day));
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    for change in lib::scaffold::new_day(root, args.day, &title)? {
+        match change {
+            Change::Created(path) => tracing::info!("Created {}", path.display()),
+            Change::Updated(path) => tracing::info!("Updated {}", path.display()),
+        }
+    }
+    tracing::info!(
+        "Paste the example into res/day{0}_test.txt and its answers into res/answers.toml, save the real input as \
+         res/day{0}.txt, then solve day{0}::part1",
+        args.day
+    );
+    Ok(())
+}
+
+#[tracing::instrument(level = "INFO", skip_all, fields(day = job.solution.day, name = job.solution.name, input = job.input_name))]
+fn run(job: Job<'_>) -> Outcome {
+    let Job {
+        solution,
+        input,
+        input_name,
+        expected,
+        repeat,
+    } = job;
+
+    let mut runs = Vec::new();
+    let mut memory = None;
+    let mut panic_msg = None;
+    let mut parse_error = None;
+    for i in 0..repeat {
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            match i == 0 && cfg!(feature = "count-allocations") {
+                true => {
+                    let (run, stats) = lib::memory::measure(|| (solution.solve_timed)(input));
+                    memory = Some(stats);
+                    run
+                }
+                false => (solution.solve_timed)(input),
+            }
+        }));
+        match result {
+            Ok(Ok(run)) => runs.push(run),
+            Ok(Err(err)) => {
+                parse_error = Some(err);
+                break;
+            }
+            Err(panic) => {
+                panic_msg = Some(
+                    panic
+                        .downcast_ref::<&str>()
+                        .map(ToString::to_string)
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_default(),
+                );
+                break;
+            }
+        }
+    }
+
+    let timings = Timings::of(&runs);
+    let answer = runs.into_iter().next().map(|run| run.answer);
+    let status = match (panic_msg, parse_error, &answer) {
+        (Some(msg), _, _) => Status::Panicked(msg),
+        (None, Some(err), _) => Status::Invalid(err),
+        (None, None, answer) => match expected {
+            None => Status::MissingExpectation,
+            Some(expected) if Some(expected) == answer.as_ref() => Status::Pass,
+            Some(_) => Status::Fail,
+        },
+    };
+
+    let result = answer.as_ref().map(ToString::to_string).unwrap_or_default();
+    let [parse, solve, total] = timings.map_or_else(Default::default, |t| {
+        [t.parse, t.solve, t.total].map(|stats| stats.to_string())
+    });
+    match &status {
+        Status::Pass | Status::MissingExpectation => match memory {
+            Some(memory) => tracing::info!(
+                %result,
+                %parse,
+                %solve,
+                %total,
+                peak = %format_bytes(memory.peak_bytes),
+                memory.allocations,
+                allocated = %format_bytes(memory.allocated_bytes)
+            ),
+            None => tracing::info!(%result, %parse, %solve, %total),
+        },
+        Status::Fail => tracing::error!(
+            %result,
+            expected = %expected.map(ToString::to_string).unwrap_or_default(),
+            %parse,
+            %solve,
+            %total,
+            "Wrong answer"
+        ),
+        Status::Panicked(msg) => tracing::error!(msg, "Solution panicked"),
+        Status::Invalid(err) => tracing::error!("Invalid input: {err}"),
+    }
+
+    Outcome {
+        solution,
+        input: input_name,
+        answer,
+        expected: expected.cloned(),
+        status,
+        timings,
+        memory,
+    }
+}
+
+fn print_report(outcomes: &[Outcome]) {
+    let repeated = outcomes.iter().filter_map(|o| o.timings).any(|t| t.runs > 1);
+    let measured = outcomes.iter().any(|o| o.memory.is_some());
+    let mut header = format!(
+        "{:>3}  {:<21}  {:<12}  {:<7}  {:>16}  {:>16}  {:>10}  {:>10}  {:>10}",
+        "day", "part", "input", "status", "answer", "expected", "parse", "solve", "total"
+    );
+    if repeated {
+        let _ = write!(header, "  {:>10}  {:>10}", "min total", "max total");
+    }
+    if measured {
+        let _ = write!(header, "  {:>10}  {:>8}  {:>10}", "peak heap", "allocs", "allocated");
+    }
+    println!("{header}");
+    for outcome in outcomes {
+        let answer = match &outcome.status {
+            Status::Panicked(msg) => msg.clone(),
+            Status::Invalid(err) => format!("line {}, column {}", err.line, err.column),
+            _ => outcome
+                .answer
+                .as_ref()
+                .map_or_else(|| String::from("-"), ToString::to_string),
+        };
+        let time = |stats: fn(&Timings) -> Duration| {
+            outcome
+                .timings
+                .as_ref()
+                .map_or_else(|| String::from("-"), |t| format_duration(stats(t)))
+        };
+        let mut line = format!(
+            "{:>3}  {:<21}  {:<12}  {:<7}  {:>16}  {:>16}  {:>10}  {:>10}  {:>10}",
+            outcome.solution.day,
+            outcome.solution.name,
+            outcome.input,
+            outcome.status.label(),
+            answer,
+            outcome
+                .expected
+                .as_ref()
+                .map_or_else(|| String::from("-"), ToString::to_string),
+            time(|t| t.parse.median),
+            time(|t| t.solve.median),
+            time(|t| t.total.median),
+        );
+        if repeated {
+            let _ = write!(line, "  {:>10}  {:>10}", time(|t| t.total.min), time(|t| t.total.max));
+        }
+        if measured {
+            let [peak, allocations, allocated] = outcome.memory.map_or_else(
+                || [(); 3].map(|()| String::from("-")),
+                |m| {
+                    [
+                        format_bytes(m.peak_bytes),
+                        m.allocations.to_string(),
+                        format_bytes(m.allocated_bytes),
+                    ]
+                },
+            );
+            let _ = write!(line, "  {peak:>10}  {allocations:>8}  {allocated:>10}");
+        }
+        println!("{line}");
+    }
+    for outcome in outcomes {
+        if let Status::Invalid(err) = &outcome.status {
+            println!(
+                "\nday {} {} with input {}: {err}",
+                outcome.solution.day, outcome.solution.name, outcome.input
+            );
+        }
+    }
+    let count = |name: &str| outcomes.iter().filter(|o| o.status.name() == name).count();
+    println!(
+        "\n{} passed, {} failed, {} missing expectation, {} panicked, {} invalid",
+        count("pass"),
+        count("fail"),
+        count("missing"),
+        count("panicked"),
+        count("invalid"),
+    );
+}
+
+/// One line of `--format json` output.
+#[derive(Debug, Serialize)]
+struct JsonOutcome<'a> {
+    day: u8,
+    part: u8,
+    /// Name of the solution function, e.g. `part2_expand_9`.
+    name: &'static str,
+    input: &'a str,
+    answer: Option<String>,
+    expected: Option<String>,
+    status: &'static str,
+    /// Panic message of a panicked solution, or the diagnostic for an invalid input.
+    error: Option<String>,
+    /// Median times of the parse and solve steps and their sum. `null` if the solution panicked.
+    parse_ns: Option<u64>,
+    solve_ns: Option<u64>,
+    total_ns: Option<u64>,
+    /// Number of successful runs the timings were taken from.
+    runs: usize,
+    /// Min, median and max of all timings.
+    timings: Option<JsonTimings>,
+    /// Heap usage of the first run. `null` unless built with the `count-allocations` feature.
+    memory: Option<JsonMemory>,
+    /// `git describe` of the build that produced this result.
+    version: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonTimings {
+    parse: JsonStats,
+    solve: JsonStats,
+    total: JsonStats,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonMemory {
+    peak_bytes: usize,
+    allocations: usize,
+    allocated_bytes: usize,
+}
+
+impl From<AllocStats> for JsonMemory {
+    fn from(stats: AllocStats) -> Self {
+        Self {
+            peak_bytes: stats.peak_bytes,
+            allocations: stats.allocations,
+            allocated_bytes: stats.allocated_bytes,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[allow(clippy::struct_field_names)]
+struct JsonStats {
+    min_ns: u64,
+    median_ns: u64,
+    max_ns: u64,
+}
+
+impl From<Stats> for JsonStats {
+    fn from(stats: Stats) -> Self {
+        Self {
+            min_ns: nanos(stats.min),
+            median_ns: nanos(stats.median),
+            max_ns: nanos(stats.max),
+        }
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+impl<'a> From<&'a Outcome> for JsonOutcome<'a> {
+    fn from(outcome: &'a Outcome) -> Self {
+        Self {
+            day: outcome.solution.day,
+            part: outcome.solution.part,
+            name: outcome.solution.name,
+            input: &outcome.input,
+            answer: outcome.answer.as_ref().map(ToString::to_string),
+            expected: outcome.expected.as_ref().map(ToString::to_string),
+            status: outcome.status.name(),
+            error: match &outcome.status {
+                Status::Panicked(msg) => Some(msg.clone()),
+                Status::Invalid(err) => Some(err.to_string()),
+                _ => None,
+            },
+            parse_ns: outcome.timings.map(|t| nanos(t.parse.median)),
+            solve_ns: outcome.timings.map(|t| nanos(t.solve.median)),
+            total_ns: outcome.timings.map(|t| nanos(t.total.median)),
+            runs: outcome.timings.map_or(0, |t| t.runs),
+            timings: outcome.timings.map(|t| JsonTimings {
+                parse: t.parse.into(),
+                solve: t.solve.into(),
+                total: t.total.into(),
+            }),
+            memory: outcome.memory.map(JsonMemory::from),
+            version: env!("GIT_DESCRIBE"),
+        }
+    }
+}
+
+fn print_json_lines(outcomes: &[Outcome]) {
+    for outcome in outcomes {
+        let line = serde_json::to_string(&JsonOutcome::from(outcome)).expect("outcomes are serializable");
+        println!("{line}");
+    }
+}
+
+/// Log to stderr, filtered by `log`, `$RUST_LOG` or at `info` level, in that order.
+/// If `trace_out` is given, additionally record all spans to that file. Returns the guard flushing it when dropped.
+///
+/// # Errors
+///
+/// Fails if the log filter is invalid or the trace file cannot be created.
+pub fn init_tracing(log: Option<&str>, trace_out: Option<&Path>) -> Result<Option<tracing_chrome::FlushGuard>, String> {
     use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
     use tracing_subscriber::util::SubscriberInitExt;
-    use tracing_subscriber::{Layer, Registry};
+    use tracing_subscriber::{EnvFilter, Layer, Registry};
 
-    fn build_log_filter(default_log_level: tracing::level_filters::LevelFilter) -> Targets {
-        Targets::new().with_default(default_log_level)
+    fn build_log_filter(log: Option<&str>) -> Result<EnvFilter, String> {
+        let (filter, source) = match (log, std::env::var(EnvFilter::DEFAULT_ENV)) {
+            (Some(log), _) => (log.to_owned(), "--log"),
+            (None, Ok(env)) => (env, EnvFilter::DEFAULT_ENV),
+            (None, Err(_)) => (String::from("info"), "default"),
+        };
+        EnvFilter::try_new(&filter).map_err(|err| format!("invalid log filter '{filter}' ({source}): {err}"))
     }
 
-    fn build_tracing_subscriber_fmt_layer() -> tracing_subscriber::fmt::Layer<Registry, Pretty, Format<Pretty>> {
+    fn build_tracing_subscriber_fmt_layer() -> impl Layer<Registry> {
         tracing_subscriber::fmt::layer()
             .pretty()
             .with_file(true)
             .with_line_number(true)
-            .with_ansi(true)
+            .with_ansi(std::io::stderr().is_terminal())
             .with_thread_names(false)
             .with_thread_ids(false)
+            .with_writer(std::io::stderr)
     }
 
-    let fmt_layer_filtered = build_tracing_subscriber_fmt_layer().with_filter(build_log_filter(level));
+    let fmt_layer_filtered = build_tracing_subscriber_fmt_layer().with_filter(build_log_filter(log)?);
+
+    let (chrome_layer, guard) = match trace_out {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .map_err(|err| format!("could not create trace file '{}': {err}", path.display()))?;
+            let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+                .writer(file)
+                .include_args(true)
+                .build();
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
 
-    Registry::default().with(fmt_layer_filtered).init();
+    Registry::default().with(fmt_layer_filtered).with(chrome_layer).init();
+    Ok(guard)
 }
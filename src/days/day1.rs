@@ -2,26 +2,47 @@ use smallvec::SmallVec;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-pub fn part1(input: &str) -> u64 {
+use crate::answer::Answer;
+use crate::parse::ParseError;
+
+pub fn parse(input: &str) -> Result<Vec<&str>, ParseError> {
     input
         .lines()
         .map(|line| {
-            let first = DigitIterator::new(line, false).next().expect("first");
-            let last = DigitIterator::new(line, false).next_back().expect("last");
-            AsciiDigit::into_u64([first, last])
+            if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii()) {
+                return Err(ParseError::new(input, &line[i..i + c.len_utf8()], "an ASCII character"));
+            }
+            match DigitIterator::new(line, true).next() {
+                Some(_) => Ok(line),
+                None => Err(ParseError::new(input, line, "a line containing a digit")),
+            }
         })
-        .sum::<u64>()
+        .collect()
 }
 
-pub fn part2(input: &str) -> u64 {
-    input
-        .lines()
-        .map(|line| {
-            let first = DigitIterator::new(line, true).next().expect("first");
-            let last = DigitIterator::new(line, true).next_back().expect("last");
-            AsciiDigit::into_u64([first, last])
-        })
-        .sum::<u64>()
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
+}
+
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
+}
+
+/// `None` if a line only contains spelled out digits, like in the example of part 2.
+pub fn part1(lines: &[&str]) -> Option<u64> {
+    lines.iter().map(|line| calibration_value(line, false)).sum()
+}
+
+pub fn part2(lines: &[&str]) -> u64 {
+    // `parse` only accepts lines containing a digit, spelled out or not.
+    lines.iter().filter_map(|line| calibration_value(line, true)).sum()
+}
+
+/// The number formed by the first and the last digit of `line`, if it contains any.
+fn calibration_value(line: &str, search_str_repr: bool) -> Option<u64> {
+    let first = DigitIterator::new(line, search_str_repr).next()?;
+    let last = DigitIterator::new(line, search_str_repr).next_back()?;
+    Some(AsciiDigit::into_u64([first, last]))
 }
 
 #[derive(Debug, Clone, Copy, EnumIter)]
@@ -182,7 +203,7 @@ impl<'a> DigitIterator<'a> {
     }
 }
 
-impl<'a> Iterator for DigitIterator<'a> {
+impl Iterator for DigitIterator<'_> {
     type Item = AsciiDigit;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -194,7 +215,7 @@ impl<'a> Iterator for DigitIterator<'a> {
     }
 }
 
-impl<'a> DoubleEndedIterator for DigitIterator<'a> {
+impl DoubleEndedIterator for DigitIterator<'_> {
     fn next_back(&mut self) -> Option<Self::Item> {
         let mut next: Option<AsciiDigit> = None;
         while next.is_none() && !self.left.is_empty() {
@@ -203,3 +224,23 @@ impl<'a> DoubleEndedIterator for DigitIterator<'a> {
         next
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{parse, part1, part2};
+
+    #[test]
+    fn test_lines_without_digits_are_rejected() {
+        let err = parse("a1b\nabc\n").expect_err("no digit");
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 1, "abc"));
+        let err = parse("é1\n").expect_err("not ASCII");
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 1, "é"));
+    }
+
+    #[test]
+    fn test_spelled_out_digits_only() {
+        let lines = parse("eightwothree\n").expect("valid input");
+        assert_eq!(part1(&lines), None);
+        assert_eq!(part2(&lines), 83);
+    }
+}
@@ -1,230 +1,127 @@
-use core::panic;
+use std::fmt::Display;
+
 use itertools::Itertools;
-use ndarray::prelude::*;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-pub fn part1(input: &str) -> u64 {
-    let mut tile_map = parse_input(input);
-
-    let start_replacement = find_candidates(&tile_map, tile_map.start_row, tile_map.start_col)
-        .exactly_one()
-        .map_err(|_err| ())
-        .expect("exactly one candidate");
+use crate::answer::Answer;
+use crate::geometry::Direction;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::visualize::{counted, Canvas, Cell, Color, Style};
 
-    tile_map.inner[[tile_map.start_row, tile_map.start_col]] = Tile::Pipe(start_replacement);
-
-    let (path, _path_map) = walk(&tile_map, tile_map.start_row, tile_map.start_col);
-    (path.len() / 2) as u64
+pub fn parse(input: &str) -> Result<TileMap, ParseError> {
+    parse_input(input)
 }
 
-pub fn part2(input: &str) -> u64 {
-    let mut tile_map = parse_input(input);
-
-    let start_replacement = find_candidates(&tile_map, tile_map.start_row, tile_map.start_col)
-        .exactly_one()
-        .map_err(|_err| ())
-        .expect("exactly one candidate");
-
-    tile_map.inner[[tile_map.start_row, tile_map.start_col]] = Tile::Pipe(start_replacement);
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
+}
 
-    let (_path, path_map) = walk(&tile_map, tile_map.start_row, tile_map.start_col);
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
+}
 
-    tile_map
-        .inner
-        .indexed_iter_mut()
-        .filter(|(_, tile)| !matches!(tile, Tile::Ground))
-        .for_each(|((r, c), tile)| {
-            if !path_map[[r, c]] {
-                *tile = Tile::Ground;
-            }
-        });
+pub const fn part1(tile_map: &TileMap) -> u64 {
+    (tile_map.loop_length / 2) as u64
+}
 
+pub fn part2(tile_map: &TileMap) -> u64 {
+    let _span = tracing::debug_span!("count_inner_tiles").entered();
     tile_map
-        .inner
-        .indexed_iter()
-        .filter(|((r, c), tile)| **tile == Tile::Ground && is_inner_tile(&tile_map, *r, *c))
+        .main_loop
+        .iter()
+        .filter(|(pos, tile)| **tile == Tile::Ground && is_inner_tile(tile_map, *pos))
         .count() as u64
 }
 
-fn find_candidates(tile_map: &TileMap, r: usize, c: usize) -> impl Iterator<Item = Pipe> + '_ {
-    Pipe::iter().filter(move |pipe| match pipe {
-        Pipe::Vertical => {
-            tile_map
-                .above(r, c)
-                .map_or(false, |t| t.is_pipe_and(Pipe::facing_south))
-                && tile_map
-                    .below(r, c)
-                    .map_or(false, |t| t.is_pipe_and(Pipe::facing_north))
-        }
-        Pipe::Horizontal => {
-            tile_map.left(r, c).map_or(false, |t| t.is_pipe_and(Pipe::facing_east))
-                && tile_map.right(r, c).map_or(false, |t| t.is_pipe_and(Pipe::facing_west))
-        }
-        Pipe::NorthEast => {
-            tile_map
-                .above(r, c)
-                .map_or(false, |t| t.is_pipe_and(Pipe::facing_south))
-                && tile_map.right(r, c).map_or(false, |t| t.is_pipe_and(Pipe::facing_west))
-        }
-        Pipe::NorthWest => {
-            tile_map
-                .above(r, c)
-                .map_or(false, |t| t.is_pipe_and(Pipe::facing_south))
-                && tile_map.left(r, c).map_or(false, |t| t.is_pipe_and(Pipe::facing_east))
-        }
-        Pipe::SouthWest => {
-            tile_map
-                .below(r, c)
-                .map_or(false, |t| t.is_pipe_and(Pipe::facing_north))
-                && tile_map.left(r, c).map_or(false, |t| t.is_pipe_and(Pipe::facing_east))
-        }
-        Pipe::SouthEast => {
-            tile_map
-                .below(r, c)
-                .map_or(false, |t| t.is_pipe_and(Pipe::facing_north))
-                && tile_map.right(r, c).map_or(false, |t| t.is_pipe_and(Pipe::facing_west))
+/// Draw the main loop in bold with the pipe found for `S` highlighted, mark the tiles inside the loop and dim the pipes
+/// not connected to it.
+pub fn visualize(tile_map: &TileMap) -> Canvas {
+    let main_loop = Style::color(Color::Cyan).bold();
+    let start = Style::color(Color::Magenta).bold();
+    let inside = Cell::new('•', Style::color(Color::Yellow).bold());
+    let other = Style::color(Color::Gray);
+
+    let mut inner_tiles = 0;
+    let canvas = Canvas::new(&tile_map.tiles, |pos, &tile| match tile_map.main_loop[pos] {
+        Tile::Pipe(pipe) if pos == tile_map.start => Cell::new(pipe.box_char(true), start),
+        Tile::Pipe(pipe) => Cell::new(pipe.box_char(true), main_loop),
+        _ if is_inner_tile(tile_map, pos) => {
+            inner_tiles += 1;
+            inside
         }
+        _ => match tile {
+            Tile::Pipe(pipe) => Cell::new(pipe.box_char(false), other),
+            _ => Cell::new('·', other),
+        },
+    });
+    let start_pipe = tile_map.main_loop[tile_map.start];
+    canvas
+        .legend(
+            Cell::new('━', main_loop),
+            format!("main loop of {}", counted(tile_map.loop_length, "tile", "tiles")),
+        )
+        .legend(
+            Cell::new('S', start),
+            format!("start, replaced by the pipe `{start_pipe}`"),
+        )
+        .legend(
+            inside,
+            format!("{} inside the loop", counted(inner_tiles, "tile", "tiles")),
+        )
+        .legend(Cell::new('─', other), "pipes not connected to the loop")
+}
+
+fn find_candidates(tiles: &Grid<Tile>, pos: Point<usize>) -> impl Iterator<Item = Pipe> + '_ {
+    Pipe::iter().filter(move |pipe| {
+        pipe.connections().into_iter().all(|direction| {
+            tiles
+                .step(pos, direction)
+                .is_some_and(|next| tiles[next].is_pipe_and(|p| p.connects(direction.opposite())))
+        })
     })
 }
 
-fn is_inner_tile(tile_map: &TileMap, r: usize, c: usize) -> bool {
+fn is_inner_tile(tile_map: &TileMap, pos: Point<usize>) -> bool {
+    // Count the pipes connecting north, so that a bend like L-7 crosses the loop once and L-J twice.
+    let crosses = |t: &Tile| t.is_pipe_and(|p| p.connects(Direction::North));
     [
-        tile_map
-            .ray_east(r, c)
-            .filter(|t| {
-                t.is_pipe_and(|p| match p {
-                    Pipe::Vertical | Pipe::NorthEast | Pipe::NorthWest => true, // |, L, J
-                    Pipe::Horizontal | Pipe::SouthWest | Pipe::SouthEast => false, // -, 7, F
-                })
-            })
-            .count(),
-        tile_map
-            .ray_west(r, c)
-            .filter(|t| {
-                t.is_pipe_and(|p| match p {
-                    Pipe::Vertical | Pipe::NorthEast | Pipe::NorthWest => true, // |, L, J
-                    Pipe::Horizontal | Pipe::SouthWest | Pipe::SouthEast => false, // -, 7, F
-                })
-            })
-            .count(),
+        tile_map.ray_east(pos).filter(crosses).count(),
+        tile_map.ray_west(pos).filter(crosses).count(),
     ]
     .into_iter()
     .all(|crossings| crossings % 2 != 0)
 }
 
-fn walk(
-    tile_map: &TileMap,
-    start_row: usize,
-    start_col: usize,
-) -> (
-    Vec<(usize, usize)>,
-    ArrayBase<ndarray::OwnedRepr<bool>, Dim<[usize; 2]>>,
-) {
-    enum Direction {
-        North,
-        East,
-        South,
-        West,
-    }
-
-    let mut from = match tile_map.inner[(start_row, start_col)] {
-        Tile::Pipe(p) => match p {
-            Pipe::Vertical | Pipe::SouthEast => Direction::South,
-            Pipe::Horizontal | Pipe::SouthWest => Direction::West,
-            Pipe::NorthEast => Direction::East,
-            Pipe::NorthWest => Direction::North,
-        },
-        _ => panic!("expected pipe"),
+/// Follow the pipes from `start` until getting back to it, returning the positions passed on the way, or the position
+/// where the loop breaks off together with what was expected there.
+#[tracing::instrument(level = "debug", skip(tiles))]
+fn walk(tiles: &Grid<Tile>, start: Point<usize>) -> Result<Vec<Point<usize>>, (Point<usize>, &'static str)> {
+    let Tile::Pipe(start_pipe) = tiles[start] else {
+        return Err((start, "a pipe"));
     };
-    let mut r = start_row;
-    let mut c = start_col;
+    let mut heading = start_pipe.connections()[0];
+    let mut pos = start;
 
     let mut path = Vec::new();
-    let mut path_map = Array2::<bool>::from_elem(tile_map.inner.raw_dim(), false);
-
     loop {
-        path.push((r, c));
-        path_map[[r, c]] = true;
-
-        match tile_map.inner[[r, c]] {
-            Tile::Pipe(p) => match p {
-                Pipe::Vertical => match from {
-                    Direction::North => {
-                        r += 1;
-                        from = Direction::North;
-                    }
-                    Direction::South => {
-                        r -= 1;
-                        from = Direction::South;
-                    }
-                    _ => panic!("wrong"),
-                },
-                Pipe::Horizontal => match from {
-                    Direction::East => {
-                        c -= 1;
-                        from = Direction::East;
-                    }
-                    Direction::West => {
-                        c += 1;
-                        from = Direction::West;
-                    }
-                    _ => panic!("wrong"),
-                },
-                Pipe::NorthEast => match from {
-                    Direction::North => {
-                        c += 1;
-                        from = Direction::West;
-                    }
-                    Direction::East => {
-                        r -= 1;
-                        from = Direction::South;
-                    }
-                    _ => panic!("wrong"),
-                },
-                Pipe::NorthWest => match from {
-                    Direction::North => {
-                        c -= 1;
-                        from = Direction::East;
-                    }
-                    Direction::West => {
-                        r -= 1;
-                        from = Direction::South;
-                    }
-                    _ => panic!("wrong"),
-                },
-                Pipe::SouthWest => match from {
-                    Direction::South => {
-                        c -= 1;
-                        from = Direction::East;
-                    }
-                    Direction::West => {
-                        r += 1;
-                        from = Direction::North;
-                    }
-                    _ => panic!("wrong"),
-                },
-                Pipe::SouthEast => match from {
-                    Direction::South => {
-                        c += 1;
-                        from = Direction::West;
-                    }
-                    Direction::East => {
-                        r += 1;
-                        from = Direction::North;
-                    }
-                    _ => panic!("wrong"),
-                },
-            },
-            _ => panic!("expected pipe"),
+        path.push(pos);
+        let next = tiles.step(pos, heading).ok_or((pos, "a loop staying inside the map"))?;
+        heading = match tiles[next] {
+            Tile::Pipe(pipe) => pipe.exit(heading.opposite()),
+            _ => None,
         }
+        .ok_or((next, "a pipe connecting to the previous one"))?;
+        pos = next;
 
-        if r == start_row && c == start_col {
+        if pos == start {
             break;
         }
     }
 
-    (path, path_map)
+    Ok(path)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
@@ -244,28 +141,46 @@ enum Pipe {
 }
 
 impl Pipe {
-    const fn facing_north(self) -> bool {
+    /// The two directions the pipe leads to.
+    const fn connections(self) -> [Direction; 2] {
         match self {
-            Self::Vertical | Self::NorthEast | Self::NorthWest => true,
-            Self::Horizontal | Self::SouthWest | Self::SouthEast => false,
+            Self::Vertical => [Direction::North, Direction::South],
+            Self::Horizontal => [Direction::East, Direction::West],
+            Self::NorthEast => [Direction::North, Direction::East],
+            Self::NorthWest => [Direction::North, Direction::West],
+            Self::SouthWest => [Direction::South, Direction::West],
+            Self::SouthEast => [Direction::South, Direction::East],
         }
     }
-    const fn facing_east(self) -> bool {
-        match self {
-            Self::Horizontal | Self::NorthEast | Self::SouthEast => true,
-            Self::Vertical | Self::SouthWest | Self::NorthWest => false,
-        }
+
+    fn connects(self, direction: Direction) -> bool {
+        self.connections().contains(&direction)
     }
-    const fn facing_south(self) -> bool {
-        match self {
-            Self::Vertical | Self::SouthWest | Self::SouthEast => true,
-            Self::Horizontal | Self::NorthEast | Self::NorthWest => false,
+
+    /// Box-drawing character of the pipe, with `heavy` lines or light ones.
+    const fn box_char(self, heavy: bool) -> char {
+        match (self, heavy) {
+            (Self::Vertical, false) => '│',
+            (Self::Horizontal, false) => '─',
+            (Self::NorthEast, false) => '└',
+            (Self::NorthWest, false) => '┘',
+            (Self::SouthWest, false) => '┐',
+            (Self::SouthEast, false) => '┌',
+            (Self::Vertical, true) => '┃',
+            (Self::Horizontal, true) => '━',
+            (Self::NorthEast, true) => '┗',
+            (Self::NorthWest, true) => '┛',
+            (Self::SouthWest, true) => '┓',
+            (Self::SouthEast, true) => '┏',
         }
     }
-    const fn facing_west(self) -> bool {
-        match self {
-            Self::Horizontal | Self::SouthWest | Self::NorthWest => true,
-            Self::Vertical | Self::NorthEast | Self::SouthEast => false,
+
+    /// The direction leaving the pipe when entering it from `entry`, if it connects to `entry` at all.
+    fn exit(self, entry: Direction) -> Option<Direction> {
+        match self.connections() {
+            [first, second] if first == entry => Some(second),
+            [first, second] if second == entry => Some(first),
+            _ => None,
         }
     }
 }
@@ -278,6 +193,20 @@ enum Tile {
 }
 
 impl Tile {
+    const fn from_char(c: char) -> Option<Self> {
+        match c {
+            '|' => Some(Self::Pipe(Pipe::Vertical)),
+            '-' => Some(Self::Pipe(Pipe::Horizontal)),
+            'L' => Some(Self::Pipe(Pipe::NorthEast)),
+            'J' => Some(Self::Pipe(Pipe::NorthWest)),
+            '7' => Some(Self::Pipe(Pipe::SouthWest)),
+            'F' => Some(Self::Pipe(Pipe::SouthEast)),
+            '.' => Some(Self::Ground),
+            'S' => Some(Self::Start),
+            _ => None,
+        }
+    }
+
     fn is_pipe_and(self, pred: impl Fn(Pipe) -> bool) -> bool {
         match self {
             Self::Pipe(pipe) => pred(pipe),
@@ -286,102 +215,123 @@ impl Tile {
     }
 }
 
-#[derive(Debug)]
-struct TileMap {
-    inner: ArrayBase<ndarray::OwnedRepr<Tile>, Dim<[usize; 2]>>,
-    start_row: usize,
-    start_col: usize,
+impl Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Self::Pipe(Pipe::Vertical) => '|',
+            Self::Pipe(Pipe::Horizontal) => '-',
+            Self::Pipe(Pipe::NorthEast) => 'L',
+            Self::Pipe(Pipe::NorthWest) => 'J',
+            Self::Pipe(Pipe::SouthWest) => '7',
+            Self::Pipe(Pipe::SouthEast) => 'F',
+            Self::Ground => '.',
+            Self::Start => 'S',
+        };
+        write!(f, "{c}")
+    }
 }
 
-impl TileMap {
-    fn height(&self) -> usize {
-        self.inner.shape()[0]
-    }
+#[derive(Debug, Clone)]
+pub struct TileMap {
+    tiles: Grid<Tile>,
+    start: Point<usize>,
+    /// The main loop with the start tile replaced by its pipe, and all other tiles replaced by ground.
+    main_loop: Grid<Tile>,
+    loop_length: usize,
+}
 
-    fn width(&self) -> usize {
-        self.inner.shape()[1]
+impl TileMap {
+    fn ray_east(&self, pos: Point<usize>) -> impl Iterator<Item = Tile> + '_ {
+        self.main_loop.row(pos.y)[pos.x + 1..].iter().copied()
     }
 
-    fn above(&self, r: usize, c: usize) -> Option<Tile> {
-        match r > 0 {
-            true => Some(self.inner[[r - 1, c]]),
-            false => None,
-        }
-    }
-    fn left(&self, r: usize, c: usize) -> Option<Tile> {
-        match c > 0 {
-            true => Some(self.inner[[r, c - 1]]),
-            false => None,
-        }
-    }
-    fn right(&self, r: usize, c: usize) -> Option<Tile> {
-        match c < self.width() {
-            true => Some(self.inner[[r, c + 1]]),
-            false => None,
-        }
-    }
-    fn below(&self, r: usize, c: usize) -> Option<Tile> {
-        match r < self.height() {
-            true => Some(self.inner[[r + 1, c]]),
-            false => None,
-        }
+    fn ray_west(&self, pos: Point<usize>) -> impl Iterator<Item = Tile> + '_ {
+        self.main_loop.row(pos.y)[..pos.x].iter().rev().copied()
     }
+}
 
-    fn ray_east(&self, row: usize, col: usize) -> impl Iterator<Item = Tile> + '_ {
-        (col..self.width()).map(move |c| self.inner[[row, c]])
+fn parse_input(input: &str) -> Result<TileMap, ParseError> {
+    let tiles = Grid::parse(input, "a tile (|, -, L, J, 7, F, . or S)", Tile::from_char)?;
+    let start = tiles
+        .position(|&tile| tile == Tile::Start)
+        .ok_or_else(|| ParseError::end_of_input(input, "a start tile (S)"))?;
+
+    let start_pipe = tracing::debug_span!("find_start_pipe").in_scope(|| {
+        find_candidates(&tiles, start).exactly_one().map_err(|_err| {
+            ParseError::new(
+                input,
+                tile_text(input, start),
+                "a start tile (S) connected to exactly two pipes",
+            )
+        })
+    })?;
+    let mut with_start_pipe = tiles.clone();
+    with_start_pipe[start] = Tile::Pipe(start_pipe);
+
+    let path = walk(&with_start_pipe, start)
+        .map_err(|(pos, expected)| ParseError::new(input, tile_text(input, pos), expected))?;
+    let mut main_loop = Grid::from_elem(tiles.width(), tiles.height(), Tile::Ground);
+    for &pos in &path {
+        main_loop[pos] = with_start_pipe[pos];
     }
 
-    fn ray_west(&self, row: usize, col: usize) -> impl Iterator<Item = Tile> + '_ {
-        (0..col).rev().map(move |c| self.inner[[row, c]])
-    }
+    Ok(TileMap {
+        tiles,
+        start,
+        main_loop,
+        loop_length: path.len(),
+    })
 }
 
-fn parse_input(input: &str) -> TileMap {
-    let rows = input.lines().count();
-    let cols = input.lines().next().map_or(0, |line| line.chars().count());
-
-    let mut map: ArrayBase<ndarray::OwnedRepr<Tile>, Dim<[usize; 2]>> = Array2::from_elem((rows, cols), Tile::Ground);
+/// The character of the tile at `pos` in `input`, which `Grid::parse` accepted.
+fn tile_text(input: &str, pos: Point<usize>) -> &str {
+    let line = input.lines().nth(pos.y).unwrap_or_else(|| &input[input.len()..]);
+    line.char_indices()
+        .nth(pos.x)
+        .map_or_else(|| &line[line.len()..], |(i, c)| &line[i..i + c.len_utf8()])
+}
 
-    input.lines().enumerate().for_each(|(r, line)| {
-        let mut num = 0;
-        for (c, tile) in parse_tiles(line).enumerate() {
-            map[[r, c]] = tile;
-            num += 1;
-        }
-        assert!(num == cols, "inconsistent line length");
-    });
+#[cfg(test)]
+mod test {
+    use super::{parse, part1, part2, visualize};
 
-    let (start_row, start_col) = map
-        .rows()
-        .into_iter()
-        .enumerate()
-        .find_map(|(r, row)| {
-            row.iter().enumerate().find_map(|(c, t)| {
-                if *t == Tile::Start {
-                    return Some((r, c));
-                }
-                None
-            })
-        })
-        .expect("start");
+    #[test]
+    fn test_loop_along_the_edges() {
+        let tile_map = parse("F-7\n|.|\nL-S\n").expect("valid input");
+        assert_eq!(part1(&tile_map), 4);
+        assert_eq!(part2(&tile_map), 1);
+        assert_eq!(tile_map.tiles.to_string(), "F-7\n|.|\nL-S\n");
+    }
 
-    TileMap {
-        inner: map,
-        start_row,
-        start_col,
+    #[test]
+    fn test_start_must_join_a_closed_loop() {
+        let err = parse("S.\n..\n").expect_err("unconnected start");
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 1, "S"));
+        let err = parse(".|.\n-S-\n.|.\n").expect_err("ambiguous start");
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 2, "S"));
+        let err = parse("S-7\n|.|\nL-.\n").expect_err("broken loop");
+        assert_eq!((err.line, err.column, err.found.as_str()), (3, 3, "."));
+        let err = parse("S-\n|.\n").expect_err("loop leaving the map");
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 1, "a loop staying inside the map")
+        );
     }
-}
 
-fn parse_tiles(line: &str) -> impl Iterator<Item = Tile> + '_ {
-    line.chars().map(|c| match c {
-        '|' => Tile::Pipe(Pipe::Vertical),
-        '-' => Tile::Pipe(Pipe::Horizontal),
-        'L' => Tile::Pipe(Pipe::NorthEast),
-        'J' => Tile::Pipe(Pipe::NorthWest),
-        '7' => Tile::Pipe(Pipe::SouthWest),
-        'F' => Tile::Pipe(Pipe::SouthEast),
-        '.' => Tile::Ground,
-        'S' => Tile::Start,
-        other => panic!("Unexpected tile: '{other}'"),
-    })
+    #[test]
+    fn test_visualize() {
+        let tile_map = parse("-F-7\n.|.|\n.L-S\n").expect("valid input");
+        let rendered = visualize(&tile_map).render(false);
+        let (picture, legend) = rendered.split_at(rendered.find(" ━").expect("legend"));
+        assert_eq!(picture, "┌────┐\n│─┏━┓│\n│·┃•┃│\n│·┗━┛│\n└────┘\n");
+        assert_eq!(
+            legend.lines().collect::<Vec<_>>(),
+            [
+                " ━ main loop of 8 tiles",
+                " S start, replaced by the pipe `J`",
+                " • 1 tile inside the loop",
+                " ─ pipes not connected to the loop"
+            ]
+        );
+    }
 }
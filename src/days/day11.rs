@@ -1,84 +1,150 @@
-use ndarray::prelude::*;
+use crate::answer::Answer;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::visualize::{counted, Canvas, Cell, Color, Style};
 
-pub fn part1(input: &str) -> u64 {
-    Universe::from_input(input, 1, 1)
-        .galaxy_combinations()
-        .map(Galaxy::manhattan_distance_tuple)
-        .sum()
+pub fn parse(input: &str) -> Result<Image, ParseError> {
+    Image::from_input(input)
 }
 
-pub fn part2_expand_9(input: &str) -> u64 {
-    Universe::from_input(input, 9, 9)
-        .galaxy_combinations()
-        .map(Galaxy::manhattan_distance_tuple)
-        .sum()
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
 }
 
-pub fn part2_expand_99(input: &str) -> u64 {
-    Universe::from_input(input, 99, 99)
-        .galaxy_combinations()
-        .map(Galaxy::manhattan_distance_tuple)
-        .sum()
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2_expand_999_999(&parse(input)?).into())
+}
+
+pub fn part1(image: &Image) -> u64 {
+    sum_of_distances(image, 1)
 }
 
-pub fn part2_expand_999_999(input: &str) -> u64 {
-    Universe::from_input(input, 999_999, 999_999)
+pub fn part2_expand_9(image: &Image) -> u64 {
+    sum_of_distances(image, 9)
+}
+
+pub fn part2_expand_99(image: &Image) -> u64 {
+    sum_of_distances(image, 99)
+}
+
+pub fn part2_expand_999_999(image: &Image) -> u64 {
+    sum_of_distances(image, 999_999)
+}
+
+/// Sum of the distances between all pairs of galaxies, after expanding each empty row and column by `expansion`.
+fn sum_of_distances(image: &Image, expansion: u64) -> u64 {
+    let universe = tracing::debug_span!("expand").in_scope(|| Universe::expand(image, expansion, expansion));
+    let _span = tracing::debug_span!("sum_distances").entered();
+    universe
         .galaxy_combinations()
-        .map(Galaxy::manhattan_distance_tuple)
+        .map(|(a, b)| a.manhattan_distance(b))
         .sum()
 }
 
+/// Draw the galaxies and mark the empty rows and columns, each of which expands into more empty ones.
+pub fn visualize(image: &Image) -> Canvas {
+    let (width, height) = (image.empty_cols_before.len(), image.empty_rows_before.len());
+    let mut galaxies = Grid::from_elem(width, height, false);
+    for &galaxy in &image.galaxies {
+        galaxies[galaxy] = true;
+    }
+    let empty_rows = galaxies.rows().map(|row| !row.contains(&true)).collect::<Vec<_>>();
+    let empty_cols = galaxies
+        .columns()
+        .map(|mut col| !col.any(|&galaxy| galaxy))
+        .collect::<Vec<_>>();
+
+    let galaxy = Cell::new('#', Style::color(Color::Yellow).bold());
+    let expanding = Cell::new('·', Style::color(Color::Cyan).on(Color::Blue));
+    let empty = Cell::new('·', Style::color(Color::Gray));
+    let mark = Style::color(Color::Cyan).bold();
+    let mut canvas = Canvas::new(&galaxies, |pos, &is_galaxy| match is_galaxy {
+        true => galaxy,
+        false if empty_rows[pos.y] || empty_cols[pos.x] => expanding,
+        false => empty,
+    });
+    for row in (0..height).filter(|&row| empty_rows[row]) {
+        canvas.mark_row(row, Cell::new('▶', mark));
+    }
+    for col in (0..width).filter(|&col| empty_cols[col]) {
+        canvas.mark_column(col, Cell::new('▼', mark));
+    }
+    let count = |empty: &[bool]| empty.iter().filter(|&&empty| empty).count();
+    canvas
+        .legend(galaxy, counted(image.galaxies.len(), "galaxy", "galaxies"))
+        .legend(
+            expanding,
+            format!(
+                "{} and {}, each expanding to 2 in part 1 and to 1000000 in part 2",
+                counted(count(&empty_rows), "empty row", "empty rows"),
+                counted(count(&empty_cols), "empty column", "empty columns")
+            ),
+        )
+}
+
+/// The observed image of the universe, before accounting for its expansion.
+#[derive(Debug)]
+pub struct Image {
+    /// Positions of the galaxies in the image.
+    galaxies: Vec<Point<usize>>,
+    /// Number of empty rows above each row.
+    empty_rows_before: Vec<u64>,
+    /// Number of empty columns left of each column.
+    empty_cols_before: Vec<u64>,
+}
+
+impl Image {
+    /// Parse the image supplied through `input`. Must only contain galaxies (`#`s) and empty space (`.`s).
+    fn from_input(input: &str) -> Result<Self, ParseError> {
+        fn count_empty_before<'a>(lines: impl Iterator<Item = impl IntoIterator<Item = &'a bool>>) -> Vec<u64> {
+            lines
+                .scan(0, |count, line| {
+                    let before = *count;
+                    *count += u64::from(!line.into_iter().any(|&galaxy| galaxy));
+                    Some(before)
+                })
+                .collect()
+        }
+
+        let image = Grid::parse(input, "a galaxy (#) or empty space (.)", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        let galaxies = image.iter().filter(|(_, &galaxy)| galaxy).map(|(pos, _)| pos).collect();
+
+        Ok(Self {
+            galaxies,
+            empty_rows_before: count_empty_before(image.rows()),
+            empty_cols_before: count_empty_before(image.columns()),
+        })
+    }
+}
+
 #[derive(Debug)]
 struct Universe {
     galaxies: Vec<Galaxy>,
 }
 
 impl Universe {
-    /// Parse the universe supplied through `input`. Must only contain galaxies (`#`s) and empty space (`.`s).
+    /// Expand the universe observed in `image`.
     ///
-    /// * `input` - The full input to parse.
     /// * `empty_row_expansion` - Each empty row (without any galaxies) is interpreted as having a width of `1 + empty_row_expansion` rows.
     /// * `empty_col_expansion` - Each empty col (without any galaxies) is interpreted as having a height of `1 + empty_col_expansion` columns.
-    fn from_input(input: &str, empty_row_expansion: u32, empty_col_expansion: u32) -> Self {
-        let rows = input.lines().count();
-        let cols = input.lines().next().map_or(0, |line| line.chars().count());
-
-        // Find empty rows and columns.
-        let mut empty_rows = Array::from_elem(rows, true);
-        let mut empty_cols = Array::from_elem(cols, true);
-        for (r, line) in input.lines().enumerate() {
-            for (c, char) in line.chars().enumerate() {
-                match char {
-                    '.' => {}
-                    _ => {
-                        empty_rows[r] = false;
-                        empty_cols[c] = false;
-                    }
-                }
-            }
-        }
-
-        // Read galaxies.
-        let mut galaxies = Vec::new();
-        input.lines().enumerate().for_each(|(r, line)| {
-            line.chars().enumerate().for_each(|(c, char)| {
-                if char == '#' {
-                    let num_empty_rows_before =
-                        u32::try_from(empty_rows.iter().take(r).filter(|e| **e).count()).expect("no truncation");
-                    let num_empty_cols_before =
-                        u32::try_from(empty_cols.iter().take(c).filter(|e| **e).count()).expect("no truncation");
-
-                    let row_expansion = num_empty_rows_before * empty_row_expansion;
-                    let col_expansion = num_empty_cols_before * empty_col_expansion;
-
-                    galaxies.push(Galaxy {
-                        x: u32::try_from(c).expect("no truncation") + col_expansion,
-                        y: u32::try_from(r).expect("no truncation") + row_expansion,
-                    });
-                }
-            });
-        });
-
+    fn expand(image: &Image, empty_row_expansion: u64, empty_col_expansion: u64) -> Self {
+        let galaxies = image
+            .galaxies
+            .iter()
+            .map(|&pos| {
+                let empty_before = Galaxy::new(image.empty_cols_before[pos.x], image.empty_rows_before[pos.y]);
+                Galaxy::new(pos.x as u64, pos.y as u64)
+                    + Galaxy::new(
+                        empty_before.x * empty_col_expansion,
+                        empty_before.y * empty_row_expansion,
+                    )
+            })
+            .collect();
         Self { galaxies }
     }
 
@@ -100,23 +166,8 @@ impl Universe {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Galaxy {
-    x: u32,
-    y: u32,
-}
-
-impl Galaxy {
-    fn manhattan_distance_tuple((g1, g2): (Self, Self)) -> u64 {
-        Self::manhattan_distance(g1, g2)
-    }
-
-    fn manhattan_distance(g1: Self, g2: Self) -> u64 {
-        let x_diff_abs = (i64::from(g2.x) - i64::from(g1.x)).unsigned_abs();
-        let y_diff_abs = (i64::from(g2.y) - i64::from(g1.y)).unsigned_abs();
-        x_diff_abs + y_diff_abs
-    }
-}
+/// Position of a galaxy in the expanded universe, `x` being its column and `y` its row.
+type Galaxy = Point<u64>;
 
 struct GalaxyCombinations<'a> {
     galaxies: &'a [Galaxy],
@@ -124,7 +175,7 @@ struct GalaxyCombinations<'a> {
     j: usize,
 }
 
-impl<'a> Iterator for GalaxyCombinations<'a> {
+impl Iterator for GalaxyCombinations<'_> {
     type Item = (Galaxy, Galaxy);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -132,17 +183,99 @@ impl<'a> Iterator for GalaxyCombinations<'a> {
             if self.j == self.galaxies.len() {
                 self.i += 1;
                 self.j = 0;
-                if self.i == self.galaxies.len() {
+                if self.i >= self.galaxies.len() {
                     return None;
                 }
             }
             let i = self.i;
             let j = self.j;
             self.j += 1;
-            match j > i {
-                true => return Some((self.galaxies[i], self.galaxies[j])),
-                false => continue,
+            if j > i {
+                return Some((self.galaxies[i], self.galaxies[j]));
+            }
+        }
+    }
+}
+
+/// Deliberately naive reference implementations, to check the solutions using expansion offsets against.
+#[cfg(any(test, feature = "oracle"))]
+pub mod oracle {
+    /// Like `sum_of_distances`, but literally inserting `expansion` copies of each empty row and column into the
+    /// image before measuring the distances between its galaxies.
+    pub fn sum_of_distances(input: &str, expansion: usize) -> u64 {
+        let image = input.lines().map(|line| line.as_bytes().to_vec()).collect::<Vec<_>>();
+        let empty_column = |c: usize| image.iter().all(|row| row[c] == b'.');
+
+        let mut universe = Vec::new();
+        for row in &image {
+            let mut expanded = Vec::new();
+            for (c, &tile) in row.iter().enumerate() {
+                let copies = match empty_column(c) {
+                    true => 1 + expansion,
+                    false => 1,
+                };
+                expanded.extend(std::iter::repeat_n(tile, copies));
+            }
+            let copies = match row.contains(&b'#') {
+                true => 1,
+                false => 1 + expansion,
+            };
+            universe.extend(std::iter::repeat_n(expanded, copies));
+        }
+
+        let galaxies = universe
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, &t)| t == b'#')
+                    .map(move |(x, _)| (x, y))
+            })
+            .collect::<Vec<_>>();
+        let mut sum = 0;
+        for (i, a) in galaxies.iter().enumerate() {
+            for b in &galaxies[i + 1..] {
+                sum += (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as u64;
             }
         }
+        sum
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{oracle, parse, part2_expand_999_999, sum_of_distances, visualize};
+
+    #[test]
+    fn test_matches_oracle_on_generated_inputs() {
+        let generator = crate::generate::generator(11).expect("generator");
+        for seed in 0..50 {
+            let input = generator.generate(seed, 12, Some(0.1));
+            let image = parse(&input).expect("valid input");
+            for expansion in [0usize, 1, 9, 99] {
+                assert_eq!(
+                    sum_of_distances(&image, expansion as u64),
+                    oracle::sum_of_distances(&input, expansion),
+                    "seed {seed}, expansion {expansion}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_distances_beyond_u32() {
+        // 4400 empty rows between two galaxies, expanding to 4.4 billion rows in part 2.
+        let input = format!("#\n{}#\n", ".\n".repeat(4400));
+        let image = parse(&input).expect("valid input");
+        assert_eq!(part2_expand_999_999(&image), 4_400_000_001);
+    }
+
+    #[test]
+    fn test_visualize() {
+        let image = parse("#..\n...\n..#\n").expect("valid input");
+        assert!(visualize(&image)
+            .render(false)
+            .starts_with("┌─▼─┐\n│#··│\n▶···│\n│··#│\n└───┘\n # 2 galaxies\n · 1 empty row and 1 empty column"));
     }
 }
@@ -5,17 +5,33 @@ use std::{
 
 use smallvec::SmallVec;
 
-pub fn part1(input: &str) -> u64 {
-    parse_entries(input, 0).map(|entry| count_arrangements(entry)).sum()
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+
+pub fn parse(input: &str) -> Result<Vec<Entry>, ParseError> {
+    parse_entries(input).collect()
+}
+
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
+}
+
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
 }
 
-pub fn part2(input: &str) -> u64 {
-    parse_entries(input, 4).map(|entry| count_arrangements(entry)).sum()
+pub fn part1(entries: &[Entry]) -> u64 {
+    entries.iter().map(count_arrangements).sum()
 }
 
-fn count_arrangements(entry: Entry) -> u64 {
+pub fn part2(entries: &[Entry]) -> u64 {
+    entries.iter().map(|entry| count_arrangements(&entry.repeated(4))).sum()
+}
+
+fn count_arrangements(entry: &Entry) -> u64 {
+    let _span = tracing::trace_span!("count_arrangements").entered();
     let mut cache = HashMap::new();
-    possible_arrangements_for_section(&entry, &mut cache, 0, 0)
+    possible_arrangements_for_section(entry, &mut cache, 0, 0)
 }
 
 fn possible_arrangements_for_section(
@@ -38,7 +54,7 @@ fn possible_arrangements_for_section(
         }
 
         // group does not contain Operational springs
-        if (0..group_len).any(|p3os| entry.springs.get(spring_ix + pos) == Some(&Condition::Operational)) {
+        if (0..group_len).any(|pos| entry.springs.get(spring_ix + pos) == Some(&Condition::Operational)) {
             return 0;
         }
 
@@ -81,7 +97,7 @@ impl Display for Condition {
 }
 
 #[derive(Debug)]
-struct Entry {
+pub struct Entry {
     springs: SmallVec<[Condition; 32]>,
     groups: SmallVec<[u8; 8]>,
 }
@@ -97,45 +113,94 @@ impl Display for Entry {
     }
 }
 
-fn parse_entries(input: &str, repetitions: usize) -> impl Iterator<Item = Entry> + '_ {
-    input.lines().map(move |line| {
-        let (springs, groups) = line.split_once(' ').expect("one space");
+impl Entry {
+    /// This entry followed by `repetitions` copies of itself, springs separated by an unknown spring.
+    fn repeated(&self, repetitions: usize) -> Self {
+        let springs = (0..=repetitions)
+            .flat_map(|rep| {
+                if rep == repetitions {
+                    self.springs.iter().chain(&[]).copied()
+                } else {
+                    self.springs.iter().chain(&[Condition::Unknown]).copied()
+                }
+            })
+            .collect();
+        let groups = (0..=repetitions).flat_map(|_| self.groups.iter().copied()).collect();
+        Self { springs, groups }
+    }
+}
+
+fn parse_entries(input: &str) -> impl Iterator<Item = Result<Entry, ParseError>> + '_ {
+    input.lines().map(|line| {
+        let (springs, groups) = line.split_once(' ').ok_or_else(|| {
+            ParseError::new(
+                input,
+                &line[line.len()..],
+                "a space between the springs and the group sizes",
+            )
+        })?;
         let springs = springs
-            .chars()
-            .map(|c| match c {
-                '.' => Condition::Operational,
-                '#' => Condition::Damaged,
-                '?' => Condition::Unknown,
-                _ => panic!("Unexpected condition character"),
+            .char_indices()
+            .map(|(i, c)| match c {
+                '.' => Ok(Condition::Operational),
+                '#' => Ok(Condition::Damaged),
+                '?' => Ok(Condition::Unknown),
+                _ => Err(ParseError::new(
+                    input,
+                    &springs[i..i + c.len_utf8()],
+                    "a spring condition (., # or ?)",
+                )),
             })
-            .collect::<SmallVec<[Condition; 32]>>();
-        let groups = groups
-            .split(',')
-            .map(|num| num.parse::<u8>().expect("valid u8 number"))
-            .collect::<SmallVec<[u8; 8]>>();
-
-        match repetitions {
-            0 => Entry { springs, groups },
-            _ => {
-                let repeated_springs = (0..=repetitions)
-                    .enumerate()
-                    .map(|(rep, _)| {
-                        if rep == repetitions {
-                            springs.iter().chain(&[]).copied()
-                        } else {
-                            springs.iter().chain(&[Condition::Unknown]).copied()
+            .collect::<Result<SmallVec<[Condition; 32]>, _>>()?;
+        let groups =
+            parse::delimited::<u8>(input, groups.as_bytes(), b',').collect::<Result<SmallVec<[u8; 8]>, _>>()?;
+        Ok(Entry { springs, groups })
+    })
+}
+
+/// Deliberately naive reference implementations, to check the memoized counting against.
+#[cfg(any(test, feature = "oracle"))]
+pub mod oracle {
+    use super::{Condition, Entry};
+
+    pub fn part1(entries: &[Entry]) -> u64 {
+        entries.iter().map(count_arrangements).sum()
+    }
+
+    pub fn part2(entries: &[Entry]) -> u64 {
+        entries.iter().map(|entry| count_arrangements(&entry.repeated(4))).sum()
+    }
+
+    /// Try every assignment of the unknown springs. Only feasible for a few unknown springs.
+    pub(super) fn count_arrangements(entry: &Entry) -> u64 {
+        let unknown = entry.springs.iter().filter(|&&s| s == Condition::Unknown).count();
+        (0..1u64 << unknown)
+            .filter(|assignment| {
+                let mut bit = 0;
+                let mut groups = Vec::new();
+                let mut run = 0u8;
+                for &spring in &entry.springs {
+                    let damaged = match spring {
+                        Condition::Damaged => true,
+                        Condition::Operational => false,
+                        Condition::Unknown => {
+                            bit += 1;
+                            assignment & (1 << (bit - 1)) != 0
                         }
-                    })
-                    .flatten()
-                    .collect();
-                let repeated_groups = (0..=repetitions).map(|_| groups.iter().copied()).flatten().collect();
-                Entry {
-                    springs: repeated_springs,
-                    groups: repeated_groups,
+                    };
+                    match damaged {
+                        true => run += 1,
+                        false if run > 0 => groups.push(std::mem::take(&mut run)),
+                        false => {}
+                    }
                 }
-            }
-        }
-    })
+                if run > 0 {
+                    groups.push(run);
+                }
+                groups.as_slice() == entry.groups.as_slice()
+            })
+            .count() as u64
+    }
 }
 
 #[cfg(test)]
@@ -144,6 +209,8 @@ mod test {
     use tracing_test::traced_test;
 
     use super::count_arrangements;
+    use super::oracle;
+    use super::parse;
     use super::parse_entries;
     use super::Condition;
     use super::Entry;
@@ -154,7 +221,7 @@ mod test {
 
     #[test]
     fn test_parse_entries_without_repetition() {
-        let parsed = parse_entries("###.## 3,2", 0).next().unwrap();
+        let parsed = parse_entries("###.## 3,2").next().expect("entry").expect("valid entry");
         assert_eq!(
             parsed.springs,
             [D, D, D, O, D, D].into_iter().collect::<SmallVec<[Condition; 32]>>()
@@ -164,7 +231,11 @@ mod test {
 
     #[test]
     fn test_parse_entries_with_repetition() {
-        let parsed = parse_entries("###.## 3,2", 1).next().unwrap();
+        let parsed = parse_entries("###.## 3,2")
+            .next()
+            .expect("entry")
+            .expect("valid entry")
+            .repeated(1);
         assert_eq!(
             parsed.springs,
             [D, D, D, O, D, D, U, D, D, D, O, D, D]
@@ -184,38 +255,52 @@ mod test {
             }
         }
 
-        assert_eq!(count_arrangements(e(&[U, U, O, U, U], &[1, 1]).view()), 4); // ??.?? 1,1
-        assert_eq!(
-            count_arrangements(e(&[U, U, D, U, O, U, U, U, U, U], &[4, 2]).view()),
-            4
-        ); // ??#?.????? 4,2
+        assert_eq!(count_arrangements(&e(&[U, U, O, U, U], &[1, 1])), 4); // ??.?? 1,1
+        assert_eq!(count_arrangements(&e(&[U, U, D, U, O, U, U, U, U, U], &[4, 2])), 4); // ??#?.????? 4,2
+        assert_eq!(count_arrangements(&e(&[U, U, U, D, U, O, U, O, U, U], &[5, 1, 1])), 2); // ???#?.?.?? 5,1,1
+        assert_eq!(count_arrangements(&e(&[U, U, U, U, U, U, D, D, U, U], &[2, 5])), 6); // ??????##?? 2,5
         assert_eq!(
-            count_arrangements(e(&[U, U, U, D, U, O, U, O, U, U], &[5, 1, 1]).view()),
-            2
-        ); // ???#?.?.?? 5,1,1
-        assert_eq!(
-            count_arrangements(e(&[U, U, U, U, U, U, D, D, U, U], &[2, 5]).view()),
-            6
-        ); // ??????##?? 2,5
-        assert_eq!(
-            count_arrangements(e(&[U, U, U, U, U, U, D, D, U, U, U, U, U, D, D], &[2, 5, 4]).view(),),
+            count_arrangements(&e(&[U, U, U, U, U, U, D, D, U, U, U, U, U, D, D], &[2, 5, 4])),
             6
         ); // ??????##?????## 2,5,4
         assert_eq!(
-            count_arrangements(e(&[U, U, U, U, U, U, D, D, U, U, U, U, U, D, D], &[2, 5, 4]).view(),),
+            count_arrangements(&e(&[U, U, U, U, U, U, D, D, U, U, U, U, U, D, D], &[2, 5, 4])),
             6
         ); // ??????##?????## 2,5,4
+        assert_eq!(count_arrangements(&e(&[U, U, U, D, U, U, O, D, U, U], &[2, 1, 2])), 2); // ???#??.#?? 2,1,2
         assert_eq!(
-            count_arrangements(e(&[U, U, U, D, U, U, O, D, U, U], &[2, 1, 2]).view()),
-            2
-        ); // ???#??.#?? 2,1,2
-        assert_eq!(
-            count_arrangements(e(&[U, U, D, U, U, U, O, U, U, U, D, U, O, O, O, D, D], &[3, 1, 3, 2]).view(),),
+            count_arrangements(&e(&[U, U, D, U, U, U, O, U, U, U, D, U, O, O, O, D, D], &[3, 1, 3, 2])),
             9
         ); // ??#???.???#?...## 3,1,3,2
         assert_eq!(
-            count_arrangements(e(&[U, U, D, U, U, U, O, U, U, U, D, U], &[3, 1, 3]).view(),),
+            count_arrangements(&e(&[U, U, D, U, U, U, O, U, U, U, D, U], &[3, 1, 3])),
             9
         ); // ??#???.???#? 3,1,3
     }
+
+    #[test]
+    fn test_matches_oracle_on_generated_inputs() {
+        let generator = crate::generate::generator(12).expect("generator");
+        for seed in 0..10 {
+            let input = generator.generate(seed, 100, Some(0.3));
+            for entry in parse(&input).expect("valid input") {
+                let unknown = entry.springs.iter().filter(|&&s| s == U).count();
+                if unknown <= 10 {
+                    assert_eq!(
+                        count_arrangements(&entry),
+                        oracle::count_arrangements(&entry),
+                        "{entry}"
+                    );
+                }
+                if unknown <= 2 {
+                    let repeated = entry.repeated(4);
+                    assert_eq!(
+                        count_arrangements(&repeated),
+                        oracle::count_arrangements(&repeated),
+                        "{entry}, repeated"
+                    );
+                }
+            }
+        }
+    }
 }
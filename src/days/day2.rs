@@ -1,22 +1,35 @@
-pub fn part1(input: &str) -> u64 {
-    input
-        .lines()
-        .map(Game::parse)
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+
+pub fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
+    input.lines().map(|line| Game::parse(input, line)).collect()
+}
+
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
+}
+
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
+}
+
+pub fn part1(games: &[Game]) -> u64 {
+    games
+        .iter()
         .filter(|game| game.max_red <= 12 && game.max_green <= 13 && game.max_blue <= 14)
         .map(|game| game.id)
         .sum::<u64>()
 }
 
-pub fn part2(input: &str) -> u64 {
-    input
-        .lines()
-        .map(Game::parse)
+pub fn part2(games: &[Game]) -> u64 {
+    games
+        .iter()
         .map(|game| game.max_red * game.max_green * game.max_blue)
         .sum::<u64>()
 }
 
-#[derive(Debug)]
-struct Game {
+#[derive(Debug, Clone, Copy)]
+pub struct Game {
     id: u64,
     max_red: u64,
     max_green: u64,
@@ -24,31 +37,34 @@ struct Game {
 }
 
 impl Game {
-    fn parse(line: &str) -> Self {
-        let (game, line) = line.split_once(':').expect("at least one ':'");
+    /// Parse one `line` of `input`, e.g. `Game 1: 3 blue, 4 red; 1 red, 2 green`.
+    fn parse(input: &str, line: &str) -> Result<Self, ParseError> {
+        let (game, line) = line
+            .split_once(':')
+            .ok_or_else(|| ParseError::new(input, &line[line.len()..], "`:` after the game id"))?;
+        let id = game
+            .strip_prefix("Game ")
+            .ok_or_else(|| ParseError::new(input, game, "`Game <id>`"))?;
         let mut game = Self {
-            id: game
-                .split_ascii_whitespace()
-                .nth(1)
-                .expect("game id")
-                .parse::<u64>()
-                .expect("game id to be number"),
+            id: parse::number(input, id)?,
             max_red: 0,
             max_green: 0,
             max_blue: 0,
         };
         for draw in line.split(';') {
             for part in draw.split(',').map(str::trim) {
-                let (num, color) = part.split_once(' ').expect("num color part");
-                let num = num.parse::<u64>().expect("draw amount to be a number");
-                match color.chars().next().expect("non empty color str") {
-                    'r' => game.max_red = u64::max(game.max_red, num),
-                    'g' => game.max_green = u64::max(game.max_green, num),
-                    'b' => game.max_blue = u64::max(game.max_blue, num),
-                    other => panic!("unexpected color: {other}"),
+                let (num, color) = part
+                    .split_once(' ')
+                    .ok_or_else(|| ParseError::new(input, part, "`<amount> <color>`"))?;
+                let num = parse::number::<u64>(input, num)?;
+                match color {
+                    "red" => game.max_red = u64::max(game.max_red, num),
+                    "green" => game.max_green = u64::max(game.max_green, num),
+                    "blue" => game.max_blue = u64::max(game.max_blue, num),
+                    other => return Err(ParseError::new(input, other, "a color (red, green or blue)")),
                 }
             }
         }
-        game
+        Ok(game)
     }
 }
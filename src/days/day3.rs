@@ -1,265 +1,202 @@
-use std::str::Lines;
+use smallvec::SmallVec;
 
-pub fn part1(input: &str) -> u64 {
-    Symbols::new(input)
-        .map(|symbol| symbol.engine_parts().sum::<u64>())
-        .sum()
-}
-
-pub fn part2(input: &str) -> u64 {
-    Symbols::new(input)
-        .filter(|symbol| symbol.symbol == '*')
-        .map(|symbol| symbol.engine_parts())
-        .filter(|parts| parts.len() == 2)
-        .map(|mut parts| {
-            let first = parts.next().expect("part 1");
-            let second = parts.next().expect("part 2");
-            first * second
-        })
-        .sum()
-}
-
-/*
-// TODO: This would be an optimization...
-pub fn part2_opt (input: &str) -> u64 {
-    Symbols::new(input)
-        .filter(|symbol| symbol.symbol == '*')
-        .filter(|symbol| symbol.estimated_num_parts_will_be_greater_then(2))
-        .map(|symbol| {
-            let mut parts = symbol.engine_parts();
-            let first = parts.next().expect("part 1");
-            let second = parts.next().expect("part 2");
-            first * second
-        })
-        .sum()
-}
-*/
-
-#[derive(Debug, Clone, Copy)]
-struct Symbol<'a> {
-    symbol: char,
-    symbol_idx: usize,
+use crate::answer::Answer;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::visualize::{counted, Canvas, Cell, Color, Style};
 
-    above: Option<&'a str>,
-    current: &'a str,
-    below: Option<&'a str>,
-}
-
-impl<'a> Symbol<'a> {
-    fn engine_parts(&self) -> EngineParts {
-        EngineParts::new(self.symbol_idx, self.above, self.current, self.below)
+pub fn parse(input: &str) -> Result<Schematic, ParseError> {
+    if input.trim().is_empty() {
+        return Err(ParseError::end_of_input(input, "an engine schematic"));
     }
+    let cells = Grid::parse(input, "a digit, a symbol or empty space (.)", |c| {
+        u8::try_from(c).ok().filter(u8::is_ascii_graphic)
+    })?;
+    Ok(Schematic { cells })
 }
 
-struct SlidingWindow<'a> {
-    above: Option<&'a str>,
-    current: &'a str,
-    below: Option<&'a str>,
-    line_iter: Lines<'a>,
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
 }
 
-impl<'a> SlidingWindow<'a> {
-    fn new(input: &'a str) -> Self {
-        let mut line_iter = input.lines();
-        Self {
-            above: None,
-            current: line_iter.next().expect("at least one line"),
-            below: line_iter.next(),
-            line_iter,
-        }
-    }
-
-    /// Returns true if end of input is already reached.
-    fn advance(&mut self) -> bool {
-        if let Some(below) = self.below {
-            self.above = Some(self.current);
-            self.current = below;
-            self.below = self.line_iter.next();
-            false
-        } else {
-            true
-        }
-    }
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
 }
 
-struct Symbols<'a> {
-    win: SlidingWindow<'a>,
-    last_symbol_idx: usize,
+pub fn part1(schematic: &Schematic) -> u64 {
+    schematic
+        .symbols()
+        .map(|(pos, _)| schematic.engine_parts(pos).iter().sum::<u64>())
+        .sum()
 }
 
-impl<'a> Symbols<'a> {
-    fn new(input: &'a str) -> Self {
-        Self {
-            win: SlidingWindow::new(input),
-            last_symbol_idx: 0,
-        }
-    }
+pub fn part2(schematic: &Schematic) -> u64 {
+    schematic
+        .symbols()
+        .filter(|&(_, symbol)| symbol == b'*')
+        .map(|(pos, _)| schematic.engine_parts(pos))
+        .filter_map(|parts| match parts.as_slice() {
+            [first, second] => Some(first * second),
+            _ => None,
+        })
+        .sum()
 }
 
-impl<'a> Iterator for Symbols<'a> {
-    type Item = Symbol<'a>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            if let Some((i, symbol)) = self.win.current[self.last_symbol_idx..]
-                .chars()
-                .enumerate()
-                .find(|(_i, c)| !matches!(c, '0'..='9' | '.'))
-            {
-                self.last_symbol_idx += i;
-                let symbol = Symbol {
-                    symbol,
-                    symbol_idx: self.last_symbol_idx,
-                    above: self.win.above,
-                    current: self.win.current,
-                    below: self.win.below,
-                };
-                if self.last_symbol_idx < self.win.current.len() {
-                    self.last_symbol_idx += 1; // would otherwise find same symbol again!
-                }
-                return Some(symbol);
-            }
-            self.last_symbol_idx = 0;
-            let end_reached = self.win.advance();
-            if end_reached {
-                return None;
+/// Draw the symbols, highlighting gears, and the numbers next to them, which are engine parts, in bold. Numbers that are
+/// no engine parts are shown in red.
+pub fn visualize(schematic: &Schematic) -> Canvas {
+    let cells = &schematic.cells;
+    let mut is_part = Grid::from_elem(cells.width(), cells.height(), false);
+    let mut gears = Vec::new();
+    for (pos, symbol) in schematic.symbols() {
+        for neighbor in cells.all_neighbors(pos).filter(|&n| cells[n].is_ascii_digit()) {
+            let start = schematic.number_start(neighbor);
+            let digits = cells.row(start.y)[start.x..]
+                .iter()
+                .take_while(|cell| cell.is_ascii_digit())
+                .count();
+            for col in start.x..start.x + digits {
+                is_part[Point::new(col, start.y)] = true;
             }
         }
-    }
-}
-
-struct EngineParts {
-    parts: [Option<u64>; 6],
-    next_part: usize,
-}
-
-impl EngineParts {
-    fn new<'a>(symbol_idx: usize, above: Option<&'a str>, current: &'a str, below: Option<&'a str>) -> Self {
-        let (above_a, above_b) = match above {
-            Some(above) => find_in_window(above, symbol_idx),
-            None => (None, None),
-        };
-        let left = find_left(current, symbol_idx);
-        let right = find_right(current, symbol_idx);
-        let (below_a, below_b) = match below {
-            Some(below) => find_in_window(below, symbol_idx),
-            None => (None, None),
-        };
-        Self {
-            parts: [above_a, above_b, left, right, below_a, below_b],
-            next_part: 0,
+        if symbol == b'*' && schematic.engine_parts(pos).len() == 2 {
+            gears.push(pos);
         }
     }
-}
-
-impl Iterator for EngineParts {
-    type Item = u64;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut next_part = None;
-        while next_part.is_none() {
-            if self.next_part < 6 {
-                next_part = self.parts[self.next_part];
-                self.next_part += 1;
-            } else {
-                break;
-            }
-        }
-        next_part
+    let part = Style::color(Color::Green).bold();
+    let no_part = Style::color(Color::Red);
+    let symbol = Style::color(Color::Yellow).bold();
+    let gear = Style::color(Color::Magenta).bold();
+    let empty = Cell::new('·', Style::color(Color::Gray));
+    Canvas::new(cells, |pos, &cell| match cell {
+        b'.' => empty,
+        b'0'..=b'9' if is_part[pos] => Cell::new(char::from(cell), part),
+        b'0'..=b'9' => Cell::new(char::from(cell), no_part),
+        _ if gears.contains(&pos) => Cell::new(char::from(cell), gear),
+        _ => Cell::new(char::from(cell), symbol),
+    })
+    .legend(Cell::new('#', symbol), "symbol")
+    .legend(
+        Cell::new('*', gear),
+        format!(
+            "{}, next to exactly two engine parts",
+            counted(gears.len(), "gear", "gears")
+        ),
+    )
+    .legend(Cell::new('7', part), "engine part, next to a symbol")
+    .legend(Cell::new('7', no_part), "number next to no symbol")
+}
+
+/// The value of `cell` if it is a digit.
+const fn digit(cell: u8) -> Option<u8> {
+    match cell {
+        b'0'..=b'9' => Some(cell - b'0'),
+        _ => None,
     }
 }
 
-impl ExactSizeIterator for EngineParts {
-    fn len(&self) -> usize {
-        self.parts.iter().filter(|it| it.is_some()).count()
-    }
+/// The engine schematic, a map of numbers, symbols and empty space (`.`).
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    cells: Grid<u8>,
 }
 
-/// Parse the full number to te left of `idx`.
-fn find_left(line: &str, idx: usize) -> Option<u64> {
-    if idx == 0 {
-        return None;
+impl Schematic {
+    /// All symbols with their positions, row by row.
+    fn symbols(&self) -> impl Iterator<Item = (Point<usize>, u8)> + '_ {
+        self.cells.rows().enumerate().flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .filter(|(_, &cell)| !matches!(cell, b'0'..=b'9' | b'.'))
+                .map(move |(col, &symbol)| (Point::new(col, row), symbol))
+        })
     }
-    line[..idx]
-        .chars()
-        .next_back()
-        .filter(char::is_ascii_digit)
-        .map(|_| parse_num(line, idx - 1))
-}
 
-/// Parse the full number to te right of `idx`.
-fn find_right(line: &str, idx: usize) -> Option<u64> {
-    if idx == line.len() - 1 {
-        return None;
+    /// The numbers adjacent to the symbol at `pos`, including diagonally: at most two above, two below and one on each
+    /// side.
+    fn engine_parts(&self, pos: Point<usize>) -> SmallVec<[u64; 6]> {
+        let is_digit = |pos: Point<usize>| self.cells[pos].is_ascii_digit();
+        self.cells
+            .all_neighbors(pos)
+            // Only the leftmost digit of each number inside the 3x3 window around `pos`, so that no number is read twice.
+            .filter(|&neighbor| {
+                is_digit(neighbor)
+                    && (neighbor.x + 1 == pos.x || neighbor.x == 0 || !is_digit(Point::new(neighbor.x - 1, neighbor.y)))
+            })
+            .map(|neighbor| self.number(self.number_start(neighbor)))
+            .collect()
     }
-    line[idx + 1..]
-        .chars()
-        .next()
-        .filter(char::is_ascii_digit)
-        .map(|_| parse_num(line, idx + 1))
-}
-
-/// Parse the full number(s) (potentially two) occurring around a three-character window
-/// whose middle index is `idx`.
-fn find_in_window(line: &str, idx: usize) -> (Option<u64>, Option<u64>) {
-    let is_left_border = idx == 0;
-    let is_right_border = idx == line.len() - 1;
 
-    let left = if is_left_border { idx } else { idx - 1 };
-    let right = if is_right_border { idx } else { idx + 1 };
-
-    let mut chars_touching = line[left..=right].chars();
-    let l = if is_left_border { None } else { chars_touching.next() };
-    let m = chars_touching.next();
-    let r = if is_right_border { None } else { chars_touching.next() };
-
-    match m.filter(char::is_ascii_digit).map(|_m| parse_num(line, idx)) {
-        Some(only_possible_num) => (Some(only_possible_num), None),
-        None => {
-            let l = l.filter(char::is_ascii_digit).map(|_| parse_num(line, left));
-            let r = r.filter(char::is_ascii_digit).map(|_| parse_num(line, right));
-            (l, r)
-        }
+    /// Start of the number occupying at least `pos`.
+    fn number_start(&self, pos: Point<usize>) -> Point<usize> {
+        let row = self.cells.row(pos.y);
+        let digits_before = row[..pos.x]
+            .iter()
+            .rev()
+            .take_while(|cell| cell.is_ascii_digit())
+            .count();
+        Point::new(pos.x - digits_before, pos.y)
     }
-}
 
-/// Parse the full number occupying at least `input[idx]`.
-fn parse_num(input: &str, idx: usize) -> u64 {
-    let min_idx = idx
-        - input[..idx]
-            .chars()
-            .rev()
-            .enumerate()
-            .find(|(_i, c)| !c.is_ascii_digit())
-            .map_or(idx, |(i, _c)| i);
-    let max_idx = idx
-        + input[idx..]
-            .chars()
-            .enumerate()
-            .find(|(_i, c)| !c.is_ascii_digit())
-            .map_or_else(|| input.len() - idx, |(i, _c)| i);
-    str::parse::<u64>(&input[min_idx..max_idx]).expect("number")
+    /// The number starting at `start`.
+    fn number(&self, start: Point<usize>) -> u64 {
+        self.cells.row(start.y)[start.x..]
+            .iter()
+            .map_while(|&cell| digit(cell))
+            .fold(0, |number, digit| number * 10 + u64::from(digit))
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::parse_num;
+    use super::{parse, visualize};
+    use crate::geometry::Point;
 
     #[test]
     fn test_read_num() {
-        assert_eq!(parse_num("123...", 0), 123);
-        assert_eq!(parse_num("123...", 1), 123);
-        assert_eq!(parse_num("123...", 2), 123);
+        let schematic = parse("123.........\n...123......\n...123......\n...123.456..\n").expect("valid input");
+        let parse_num = |row, col| schematic.number(schematic.number_start(Point::new(col, row)));
+        assert_eq!(parse_num(0, 0), 123);
+        assert_eq!(parse_num(0, 1), 123);
+        assert_eq!(parse_num(0, 2), 123);
+
+        assert_eq!(parse_num(1, 3), 123);
+        assert_eq!(parse_num(1, 4), 123);
+        assert_eq!(parse_num(1, 5), 123);
+
+        assert_eq!(parse_num(2, 3), 123);
+        assert_eq!(parse_num(2, 4), 123);
+        assert_eq!(parse_num(2, 5), 123);
+
+        assert_eq!(parse_num(3, 5), 123);
+        assert_eq!(parse_num(3, 7), 456);
+    }
 
-        assert_eq!(parse_num("...123", 3), 123);
-        assert_eq!(parse_num("...123", 4), 123);
-        assert_eq!(parse_num("...123", 5), 123);
+    #[test]
+    fn test_ragged_input_is_rejected() {
+        let err = parse("123...\n...123\n...123...\n...123.456..\n").expect_err("ragged lines");
+        assert_eq!((err.line, err.column, err.found.as_str()), (3, 7, "..."));
+    }
 
-        assert_eq!(parse_num("...123...", 3), 123);
-        assert_eq!(parse_num("...123...", 4), 123);
-        assert_eq!(parse_num("...123...", 5), 123);
+    #[test]
+    fn test_engine_parts_touching_the_edges() {
+        let schematic = parse("1.2\n.*.\n3.4\n").expect("valid input");
+        assert_eq!(schematic.engine_parts(Point::new(1, 1)).as_slice(), [2, 4, 3, 1]);
+        let schematic = parse("12*34\n").expect("valid input");
+        assert_eq!(schematic.engine_parts(Point::new(2, 0)).as_slice(), [34, 12]);
+    }
 
-        assert_eq!(parse_num("...123.456..", 5), 123);
-        assert_eq!(parse_num("...123.456..", 7), 456);
+    #[test]
+    fn test_visualize() {
+        let schematic = parse("467..114\n...*....\n..35.#..\n").expect("valid input");
+        let rendered = visualize(&schematic).render(false);
+        assert!(rendered.starts_with("┌────────┐\n│467··114│\n│···*····│\n│··35·#··│\n└────────┘\n"));
+        assert!(rendered.contains(" * 1 gear, next to exactly two engine parts\n"));
+        let colored = visualize(&schematic).render(true);
+        // 114 is no engine part, shown in red.
+        assert!(colored.contains("\x1b[0;31m114\x1b[0m"));
     }
 }
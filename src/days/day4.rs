@@ -1,8 +1,28 @@
-pub fn part1(input: &str) -> u64 {
+use smallvec::SmallVec;
+
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+
+pub fn parse(input: &str) -> Result<Vec<Card>, ParseError> {
     input
         .lines()
-        .map(Card::init)
-        .map(|(card, our_numbers)| our_numbers.filter(|num| card.is_winning(*num)).count())
+        .zip(1..)
+        .map(|(line, id)| Card::parse(input, line, id))
+        .collect()
+}
+
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
+}
+
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
+}
+
+pub fn part1(cards: &[Card]) -> u64 {
+    cards
+        .iter()
+        .map(Card::count_winning)
         .map(|count| u32::try_from(count).expect("count to be in u32 range"))
         .map(|count| match count {
             0 => 0,
@@ -11,68 +31,107 @@ pub fn part1(input: &str) -> u64 {
         .sum::<u64>()
 }
 
-pub fn part2(input: &str) -> u64 {
-    const N: usize = 206;
-    let mut copies = [1u32; N];
+pub fn part2(cards: &[Card]) -> u64 {
+    let mut copies = vec![1u32; cards.len()];
 
-    input.lines().map(Card::init).for_each(|(card, our_numbers)| {
-        let idx = card.id as usize - 1;
+    for (idx, card) in cards.iter().enumerate() {
         let copies_of_current_card = copies[idx];
 
-        let count_winning = our_numbers.filter(|num| card.is_winning(*num)).count();
-        for offset in 1..=count_winning {
-            copies[usize::min(idx + offset, N - 1)] += copies_of_current_card;
+        // There are no cards to win past the end of the table.
+        let won = idx + 1..usize::min(idx + 1 + card.count_winning(), cards.len());
+        for copies_of_won_card in &mut copies[won] {
+            *copies_of_won_card += copies_of_current_card;
         }
-    });
+    }
 
     u64::from(copies.into_iter().sum::<u32>())
 }
 
 #[derive(Debug)]
-struct Card {
-    id: u32,
-    winning_bitmask: u128,
+pub struct Card {
+    /// Bit `n % 128` of the `n / 128`th mask is set if `n` is a winning number.
+    winning_bitmask: [u128; 2],
+    our_numbers: SmallVec<[u8; 32]>,
 }
 
 impl Card {
-    fn init(line: &str) -> (Self, impl Iterator<Item = u8> + '_) {
-        let (card, rest) = line.split_once(':').expect("at least one ':'");
-        let id = card
-            .trim_start_matches("Card")
-            .trim_start()
-            .parse::<u32>()
-            .expect("card id to be number");
+    /// Parse one `line` of `input`, e.g. `Card 1: 41 48 83 | 83 86  6 31 17`, which must be the card with `id`.
+    fn parse(input: &str, line: &str, id: u32) -> Result<Self, ParseError> {
+        let (card, rest) = line
+            .split_once(':')
+            .ok_or_else(|| ParseError::new(input, &line[line.len()..], "`:` after the card id"))?;
+        let id_text = card
+            .strip_prefix("Card")
+            .ok_or_else(|| ParseError::new(input, card, "`Card <id>`"))?
+            .trim_start();
+        if parse::integer::<u32>(input, id_text.as_bytes())? != id {
+            return Err(ParseError::new(
+                input,
+                id_text,
+                format!("card {id}, as cards are numbered 1, 2, 3, ..."),
+            ));
+        }
 
         let (winning_numbers, our_numbers) = rest
             .split_once('|')
-            .map(|(w, y)| (parse_numbers(w), parse_numbers(y)))
-            .expect("at least one ':'");
+            .ok_or_else(|| ParseError::new(input, &rest[rest.len()..], "`|` between the winning and our numbers"))?;
 
-        let winning_bitmask: u128 = winning_numbers.fold(0u128, |bitmask, num| bitmask | (1 << num));
+        let mut winning_bitmask = [0u128; 2];
+        for num in parse::integers::<u8>(input, winning_numbers.as_bytes()) {
+            let num = num?;
+            winning_bitmask[usize::from(num >> 7)] |= 1 << (num & 127);
+        }
 
-        (Self { id, winning_bitmask }, our_numbers)
+        Ok(Self {
+            winning_bitmask,
+            our_numbers: parse::integers(input, our_numbers.as_bytes()).collect::<Result<_, _>>()?,
+        })
     }
 
-    fn is_winning(&self, num: u8) -> bool {
-        debug_assert!((0..=127).contains(&num));
-        self.winning_bitmask & (1 << num) != 0
+    fn count_winning(&self) -> usize {
+        self.our_numbers.iter().filter(|num| self.is_winning(**num)).count()
     }
-}
 
-fn parse_numbers(line: &str) -> impl Iterator<Item = u8> + '_ {
-    line.split_ascii_whitespace().map(|num| match num.len() {
-        1 => num.as_bytes()[0] - b'0',
-        2 => 10 * (num.as_bytes()[0] - b'0') + (num.as_bytes()[1] - b'0'),
-        _ => panic!("Only 2-digit numbers are supported! Received: {num}"),
-    })
+    fn is_winning(&self, num: u8) -> bool {
+        self.winning_bitmask[usize::from(num >> 7)] & (1 << (num & 127)) != 0
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::parse_numbers;
+    use super::{parse, part2, Card};
+
+    #[test]
+    fn test_numbers_of_any_width() {
+        let line = "Card 1: 1 255  7 | 255 7 100  1";
+        let card = Card::parse(line, line, 1).expect("valid card");
+        assert_eq!(card.our_numbers.as_slice(), [255, 7, 100, 1]);
+        assert_eq!(card.count_winning(), 3);
+    }
+
+    #[test]
+    fn test_numbers_out_of_range() {
+        let line = "Card 1: 1 256 | 1";
+        let err = Card::parse(line, line, 1).expect_err("too large");
+        assert_eq!((err.column, err.found.as_str()), (11, "256"));
+    }
+
+    #[test]
+    fn test_card_ids_must_count_up_from_one() {
+        for (input, found) in [("Card 0: 1 | 1\n", "0"), ("Card 1: 1 | 1\nCard 3: 1 | 1\n", "3")] {
+            let err = parse(input).expect_err("wrong id");
+            assert_eq!(err.found, found);
+            assert_eq!(
+                err.expected,
+                format!("card {}, as cards are numbered 1, 2, 3, ...", err.line)
+            );
+        }
+    }
 
     #[test]
-    fn test_parse_numbers() {
-        assert_eq!(vec![1, 2, 42, 99], parse_numbers(" 1  2 42   99  ").collect::<Vec<_>>())
+    fn test_no_copies_of_cards_past_the_end() {
+        let cards = parse("Card 1: 1 2 | 1 2\nCard 2: 3 | 3\n").expect("valid cards");
+        // Card 1 wins a copy of card 2, and none of the missing card 3.
+        assert_eq!(part2(&cards), 3);
     }
 }
@@ -1,15 +1,31 @@
 use itertools::Itertools;
 use smallvec::SmallVec;
-use std::{ops::Range, str::Lines};
+use std::str::Lines;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-pub fn part1(input: &str) -> Option<i64> {
-    let (seeds, config) = read_input(input, SeedInterpretation::Single);
-    seeds
-        .expect_single()
-        .into_iter()
-        .map(|seed| {
+use crate::answer::Answer;
+use crate::interval::{Interval, IntervalSet};
+use crate::parse::{self, ParseError};
+
+pub fn parse(input: &str) -> Result<Almanac, ParseError> {
+    read_input(input)
+}
+
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
+}
+
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
+}
+
+pub fn part1(almanac: &Almanac) -> Option<i64> {
+    let config = &almanac.config;
+    almanac
+        .seeds
+        .iter()
+        .map(|&seed| {
             let soil = config.seed_to_soil.project(seed);
             let fertilizer = config.soil_to_fertilizer.project(soil);
             let water = config.fertilizer_to_water.project(fertilizer);
@@ -21,67 +37,43 @@ pub fn part1(input: &str) -> Option<i64> {
         .min()
 }
 
-pub fn part2(input: &str) -> Option<i64> {
-    let (seeds, config) = read_input(input, SeedInterpretation::Range);
-    seeds
-        .expect_ranges()
-        .into_iter()
-        .fold(None, |prev_lowest_location, seed_range| {
-            let mut to_soil = SmallVec::<[Projection; 32]>::new();
-            config.seed_to_soil.project_range(seed_range, &mut to_soil);
-            let to_fertilizer = config.soil_to_fertilizer.project_projections(to_soil);
-            let to_water = config.fertilizer_to_water.project_projections(to_fertilizer);
-            let to_light = config.water_to_light.project_projections(to_water);
-            let to_temperature = config.light_to_temperature.project_projections(to_light);
-            let to_humidity = config.temperature_to_humidity.project_projections(to_temperature);
-            let to_location = config.humidity_to_location.project_projections(to_humidity);
-
-            let min_location = to_location
-                .into_iter()
-                .map(|p| p.target_range().start)
-                .min()
-                .expect("at least one location projection");
-
-            Some(i64::min(min_location, prev_lowest_location.unwrap_or(i64::MAX)))
-        })
-}
-
-#[derive(Debug, Clone, Copy)]
-struct MyRange {
-    start: i64,
-    end: i64, // exclusive
+pub fn part2(almanac: &Almanac) -> Option<i64> {
+    let config = &almanac.config;
+    let seeds = seed_ranges(&almanac.seeds).into_iter().collect::<IntervalSet<_>>();
+    let _span = tracing::trace_span!("project_seed_ranges", ranges = seeds.intervals().len()).entered();
+    [
+        &config.seed_to_soil,
+        &config.soil_to_fertilizer,
+        &config.fertilizer_to_water,
+        &config.water_to_light,
+        &config.light_to_temperature,
+        &config.temperature_to_humidity,
+        &config.humidity_to_location,
+    ]
+    .into_iter()
+    .fold(seeds, |values, projections| projections.project_set(&values))
+    .min()
 }
 
-impl MyRange {
-    const fn range(&self) -> Range<i64> {
-        self.start..self.end
-    }
-
-    fn overlaps(&self, other: &Self) -> Option<(i64, i64)> {
-        let max_start = i64::max(self.start, other.start);
-        let min_end = i64::min(self.end, other.end);
-        match max_start < min_end {
-            true => Some((max_start, min_end)),
-            false => None,
-        }
+/// Interpret the seeds as pairs of range start and range length.
+fn seed_ranges(seeds: &[i64]) -> Vec<Interval<i64>> {
+    let mut tuples = seeds.iter().copied().tuples();
+    let ranges = tuples
+        .by_ref()
+        .map(|(start, len)| Interval::new(start, start + len))
+        .collect_vec();
+    for leftover_seed in tuples.into_buffer() {
+        tracing::warn!(leftover_seed, "found leftover when reading seeds as range pairs");
     }
+    ranges
 }
 
 #[derive(Debug)]
 struct Projection {
-    source_range: MyRange,
+    source_range: Interval<i64>,
     offset: i64, // No range here, as the target range has the same size as the source range!
 }
 
-impl Projection {
-    const fn target_range(&self) -> MyRange {
-        MyRange {
-            start: self.source_range.start + self.offset,
-            end: self.source_range.end + self.offset,
-        }
-    }
-}
-
 #[derive(Debug)]
 struct Projections {
     ty: ProjectionType,
@@ -91,85 +83,26 @@ struct Projections {
 impl Projections {
     fn project(&self, value: i64) -> i64 {
         for mapping in &self.projections {
-            if mapping.source_range.range().contains(&value) {
+            if mapping.source_range.contains(value) {
                 return value + mapping.offset;
             }
         }
         value
     }
 
-    fn project_range(&self, source_range: MyRange, out: &mut SmallVec<[Projection; 32]>) {
+    /// Project all `values` at once. Values outside of every source range keep their value.
+    fn project_set(&self, values: &IntervalSet<i64>) -> IntervalSet<i64> {
+        let mut unmapped = values.clone();
+        let mut projected = IntervalSet::new();
         for mapping in &self.projections {
-            if let Some((max_start, min_end)) = source_range.overlaps(&mapping.source_range) {
-                out.push(Projection {
-                    source_range: MyRange {
-                        start: max_start,
-                        end: min_end,
-                    },
-                    offset: mapping.offset,
-                });
-                out.sort_by_key(|p| p.source_range.start);
-            }
-        }
-
-        if out.is_empty() {
-            out.push(Projection {
-                source_range,
-                offset: 0,
-            });
-        }
-
-        // Add identity projections. Every range not covered with offset 0.
-        let mut next = source_range.start;
-        let mut filler = SmallVec::<[Projection; 32]>::new();
-        for p in out.as_ref() {
-            if p.source_range.start > next {
-                let identity = Projection {
-                    source_range: MyRange {
-                        start: next,
-                        end: p.source_range.start,
-                    },
-                    offset: 0,
-                };
-                filler.push(identity);
-            }
-            next = p.source_range.end;
-        }
-
-        out.append(&mut filler);
-        out.sort_by_key(|p| p.source_range.start);
-    }
-
-    fn project_projections(&self, source_projections: SmallVec<[Projection; 32]>) -> SmallVec<[Projection; 32]> {
-        let mut projections = SmallVec::<[Projection; 32]>::new();
-        let mut out = SmallVec::<[Projection; 32]>::new();
-        for source_projection in source_projections {
-            self.project_range(source_projection.target_range(), &mut out);
-            projections.append(&mut out);
-        }
-        //projections.sort_by_key(|p| p.source_range.start);
-        projections
-    }
-}
-
-#[derive(Debug)]
-enum Seeds {
-    Single(Vec<i64>),
-    Ranges(Vec<MyRange>),
-}
-
-impl Seeds {
-    fn expect_single(self) -> Vec<i64> {
-        match self {
-            Self::Single(single) => single,
-            Self::Ranges(_) => panic!("unexpected"),
-        }
-    }
-    fn expect_ranges(self) -> Vec<MyRange> {
-        match self {
-            Self::Single(_) => panic!("unexpected"),
-            Self::Ranges(ranges) => ranges,
+            projected.extend(
+                values
+                    .overlapping(mapping.source_range)
+                    .map(|overlap| overlap.shift(mapping.offset)),
+            );
+            unmapped.remove(mapping.source_range);
         }
+        projected.union(&unmapped)
     }
 }
 
@@ -177,28 +110,21 @@ impl Seeds {
 #[allow(clippy::large_enum_variant)]
 enum InputPart {
     Seeds(Vec<i64>),
-    SeedRanges(Vec<MyRange>),
     Projections(Projections),
 }
 
-#[derive(Debug, Clone, Copy)]
-enum SeedInterpretation {
-    Single,
-    Range,
-}
-
 struct InputParser<'a> {
-    seed_interpretation: SeedInterpretation,
+    input: &'a str,
     lines: Lines<'a>,
     line: Option<&'a str>,
     in_block: Option<Projections>,
 }
 
 impl<'a> InputParser<'a> {
-    fn new(input: &'a str, seed_interpretation: SeedInterpretation) -> Self {
+    fn new(input: &'a str) -> Self {
         let mut lines = input.lines();
         Self {
-            seed_interpretation,
+            input,
             line: lines.next(),
             lines,
             in_block: None,
@@ -206,45 +132,26 @@ impl<'a> InputParser<'a> {
     }
 }
 
-impl<'a> InputParser<'a> {
-    fn read_seeds<I: Iterator<Item = i64>>(it: I, seed_interpretation: SeedInterpretation) -> InputPart {
-        match seed_interpretation {
-            SeedInterpretation::Single => {
-                let seeds = it.collect::<Vec<_>>();
-                InputPart::Seeds(seeds)
-            }
-            SeedInterpretation::Range => {
-                let mut tuples = it.tuples();
-                let ranges = tuples
-                    .by_ref()
-                    .map(|(start, len)| MyRange {
-                        start,
-                        end: start + len,
-                    })
-                    .collect_vec();
-                for leftover_seed in tuples.into_buffer() {
-                    tracing::warn!(leftover_seed, "found leftover when reading seeds as range pairs");
-                }
-                InputPart::SeedRanges(ranges)
-            }
+impl InputParser<'_> {
+    /// Parse a line of a block, e.g. `50 98 2`.
+    fn read_projection(&self, line: &str) -> Result<Projection, ParseError> {
+        let mut numbers = parse::integers::<i64>(self.input, line.as_bytes());
+        let mut next = |expected: &str| {
+            numbers
+                .next()
+                .unwrap_or_else(|| Err(ParseError::new(self.input, &line[line.len()..], expected)))
+        };
+        let target_start = next("the target range start")?;
+        let source_start = next("the source range start")?;
+        let len = next("the range length")?;
+        let extra = numbers.remainder().trim_ascii();
+        if !extra.is_empty() {
+            return Err(ParseError::at_bytes(self.input, extra, "the end of the line"));
         }
-    }
-
-    fn read_projection(line: &str) -> Projection {
-        let mut it = line
-            .split_ascii_whitespace()
-            .map(|it| it.parse::<i64>().expect("number"));
-        let target_start = it.next().expect("target_start");
-        let source_start = it.next().expect("source_start");
-        let len = it.next().expect("len");
-        assert_eq!(it.next(), None);
-        Projection {
-            source_range: MyRange {
-                start: source_start,
-                end: source_start + len,
-            },
+        Ok(Projection {
+            source_range: Interval::new(source_start, source_start + len),
             offset: target_start - source_start,
-        }
+        })
     }
 
     fn start_block(&mut self, ty: ProjectionType) {
@@ -254,7 +161,7 @@ impl<'a> InputParser<'a> {
         });
     }
 
-    fn finish_block(&mut self) -> Option<Projections> {
+    const fn finish_block(&mut self) -> Option<Projections> {
         self.in_block.take()
     }
 
@@ -263,32 +170,35 @@ impl<'a> InputParser<'a> {
     }
 }
 
-impl<'a> Iterator for InputParser<'a> {
-    type Item = InputPart;
+impl Iterator for InputParser<'_> {
+    type Item = Result<InputPart, ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         'outer: loop {
             match self.line {
                 Some(line) => {
                     if let Some(line) = line.strip_prefix("seeds:") {
-                        let it = line
-                            .trim_start()
-                            .split_ascii_whitespace()
-                            .map(|it| it.parse::<i64>().expect("number"));
+                        let seeds = parse::integers::<i64>(self.input, line.as_bytes()).collect::<Result<_, _>>();
                         self.next_line();
-                        return Some(InputParser::read_seeds(it, self.seed_interpretation));
+                        return Some(seeds.map(InputPart::Seeds));
                     }
 
                     if line.is_empty() {
                         self.next_line();
                         if let Some(block) = self.finish_block() {
-                            return Some(InputPart::Projections(block));
+                            return Some(Ok(InputPart::Projections(block)));
                         }
                         continue 'outer;
                     }
 
-                    if let Some(projections) = &mut self.in_block {
-                        projections.projections.push(InputParser::read_projection(line));
+                    if self.in_block.is_some() {
+                        let projection = match self.read_projection(line) {
+                            Ok(projection) => projection,
+                            Err(err) => return Some(Err(err)),
+                        };
+                        if let Some(projections) = &mut self.in_block {
+                            projections.projections.push(projection);
+                        }
                         self.next_line();
                         continue 'outer;
                     }
@@ -301,20 +211,27 @@ impl<'a> Iterator for InputParser<'a> {
                         }
                     }
 
-                    tracing::warn!(line, "We do not understand this input...");
-                    return None;
+                    return Some(Err(ParseError::new(
+                        self.input,
+                        line,
+                        "`seeds: <numbers>` or a `<source>-to-<target> map:` block",
+                    )));
                 }
                 None => {
-                    if let Some(block) = self.finish_block() {
-                        return Some(InputPart::Projections(block));
-                    }
-                    return None;
+                    return self.finish_block().map(|block| Ok(InputPart::Projections(block)));
                 }
             }
         }
     }
 }
 
+/// The parsed puzzle input: the seeds and all projections from seeds to locations.
+#[derive(Debug)]
+pub struct Almanac {
+    seeds: Vec<i64>,
+    config: Config,
+}
+
 #[derive(Debug)]
 struct Config {
     seed_to_soil: Projections,
@@ -326,8 +243,8 @@ struct Config {
     humidity_to_location: Projections,
 }
 
-fn read_input(input: &str, seed_interpretation: SeedInterpretation) -> (Seeds, Config) {
-    let mut seeds: Option<Seeds> = None;
+fn read_input(input: &str) -> Result<Almanac, ParseError> {
+    let mut seeds: Option<Vec<i64>> = None;
     let mut seed_to_soil: Option<Projections> = None;
     let mut soil_to_fertilizer: Option<Projections> = None;
     let mut fertilizer_to_water: Option<Projections> = None;
@@ -336,10 +253,9 @@ fn read_input(input: &str, seed_interpretation: SeedInterpretation) -> (Seeds, C
     let mut temperature_to_humidity: Option<Projections> = None;
     let mut humidity_to_location: Option<Projections> = None;
 
-    for part in InputParser::new(input, seed_interpretation) {
-        match part {
-            InputPart::Seeds(single) => seeds = Some(Seeds::Single(single)),
-            InputPart::SeedRanges(ranges) => seeds = Some(Seeds::Ranges(ranges)),
+    for part in InputParser::new(input) {
+        match part? {
+            InputPart::Seeds(single) => seeds = Some(single),
             InputPart::Projections(mappings) => match mappings.ty {
                 ProjectionType::SeedToSoil => seed_to_soil = Some(mappings),
                 ProjectionType::SoilToFertilizer => soil_to_fertilizer = Some(mappings),
@@ -351,18 +267,21 @@ fn read_input(input: &str, seed_interpretation: SeedInterpretation) -> (Seeds, C
             },
         }
     }
-    (
-        seeds.expect("seeds"),
-        Config {
-            seed_to_soil: seed_to_soil.expect("block"),
-            soil_to_fertilizer: soil_to_fertilizer.expect("block"),
-            fertilizer_to_water: fertilizer_to_water.expect("block"),
-            water_to_light: water_to_light.expect("block"),
-            light_to_temperature: light_to_temperature.expect("block"),
-            temperature_to_humidity: temperature_to_humidity.expect("block"),
-            humidity_to_location: humidity_to_location.expect("block"),
+    let block = |projections: Option<Projections>, ty: ProjectionType| {
+        projections.ok_or_else(|| ParseError::end_of_input(input, format!("a `{} map:` block", ty.block_name())))
+    };
+    Ok(Almanac {
+        seeds: seeds.ok_or_else(|| ParseError::end_of_input(input, "a `seeds:` line"))?,
+        config: Config {
+            seed_to_soil: block(seed_to_soil, ProjectionType::SeedToSoil)?,
+            soil_to_fertilizer: block(soil_to_fertilizer, ProjectionType::SoilToFertilizer)?,
+            fertilizer_to_water: block(fertilizer_to_water, ProjectionType::FertilizerToWater)?,
+            water_to_light: block(water_to_light, ProjectionType::WaterToLight)?,
+            light_to_temperature: block(light_to_temperature, ProjectionType::LightToTemperature)?,
+            temperature_to_humidity: block(temperature_to_humidity, ProjectionType::TemperatureToHumidity)?,
+            humidity_to_location: block(humidity_to_location, ProjectionType::HumidityToLocation)?,
         },
-    )
+    })
 }
 
 #[derive(Debug, Clone, Copy, EnumIter)]
@@ -389,3 +308,78 @@ impl ProjectionType {
         }
     }
 }
+
+/// Deliberately naive reference implementations, to check the projection of ranges against.
+#[cfg(any(test, feature = "oracle"))]
+pub mod oracle {
+    use super::{seed_ranges, Almanac};
+
+    /// Project every single seed of every seed range.
+    pub fn part2(almanac: &Almanac) -> Option<i64> {
+        let config = &almanac.config;
+        seed_ranges(&almanac.seeds)
+            .into_iter()
+            .flat_map(|seeds| seeds.range())
+            .map(|seed| {
+                [
+                    &config.soil_to_fertilizer,
+                    &config.fertilizer_to_water,
+                    &config.water_to_light,
+                    &config.light_to_temperature,
+                    &config.temperature_to_humidity,
+                    &config.humidity_to_location,
+                ]
+                .into_iter()
+                .fold(config.seed_to_soil.project(seed), |value, projections| {
+                    projections.project(value)
+                })
+            })
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt::Write;
+
+    use super::{oracle, parse, part2};
+    use crate::generate::Rng;
+
+    /// An almanac with small numbers, so that the oracle can project every seed.
+    fn small_almanac(rng: &mut Rng) -> String {
+        let mut input = String::from("seeds:");
+        for _ in 0..rng.range(1..=3) {
+            let _ = write!(input, " {} {}", rng.range(0..=60), rng.range(1..=40));
+        }
+        input.push('\n');
+        for name in [
+            "seed-to-soil",
+            "soil-to-fertilizer",
+            "fertilizer-to-water",
+            "water-to-light",
+            "light-to-temperature",
+            "temperature-to-humidity",
+            "humidity-to-location",
+        ] {
+            let _ = write!(input, "\n{name} map:\n");
+            let mut source = 0;
+            for _ in 0..rng.range(1..=4) {
+                source += rng.range(0..=20);
+                let len = rng.range(1..=20);
+                let _ = writeln!(input, "{} {source} {len}", rng.range(0..=100));
+                source += len;
+            }
+        }
+        input
+    }
+
+    #[test]
+    fn test_matches_oracle_on_random_inputs() {
+        let mut rng = Rng::new(5);
+        for _ in 0..500 {
+            let input = small_almanac(&mut rng);
+            let almanac = parse(&input).expect("valid input");
+            assert_eq!(part2(&almanac), oracle::part2(&almanac), "{input}");
+        }
+    }
+}
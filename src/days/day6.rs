@@ -1,14 +1,38 @@
-pub fn part1(input: &str) -> u32 {
-    parse_individual_games(input)
-        .map(|g| solve(g.duration, g.distance_record, 1))
-        .map(WinningDuration::num_options_to_win)
-        .fold(None, |acc, next| Some(acc.unwrap_or(1) * next))
-        .expect("at least one game")
+use crate::answer::Answer;
+use crate::math;
+use crate::parse::{self, Integer, ParseError};
+
+pub fn parse(input: &str) -> Result<Races, ParseError> {
+    let mut lines = input.lines();
+    let times = labeled_values(input, lines.next(), "Time:")?;
+    let distances = labeled_values(input, lines.next(), "Distance:")?;
+    Ok(Races {
+        games: parse_individual_games(input, times, distances)?,
+        long_game: Game {
+            duration: parse_long_number(input, times)?,
+            distance_record: parse_long_number(input, distances)?,
+        },
+    })
+}
+
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
+}
+
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
+}
+
+pub fn part1(races: &Races) -> u32 {
+    races.games.iter().map(num_options_to_win).product()
+}
+
+pub fn part2(races: &Races) -> u32 {
+    num_options_to_win(&races.long_game)
 }
 
-pub fn part2(input: &str) -> u32 {
-    let g = parse_long_game(input);
-    solve(g.duration, g.distance_record, 1).num_options_to_win()
+fn num_options_to_win(game: &Game) -> u32 {
+    solve(game.duration, game.distance_record, 1).map_or(0, WinningDuration::num_options_to_win)
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -23,84 +47,155 @@ impl WinningDuration {
     }
 }
 
-#[allow(clippy::cast_possible_truncation)]
-fn solve(t_run: u64, dist_record: u64, v: u8) -> WinningDuration {
+/// The press times beating `dist_record`, `None` if it cannot be beaten.
+fn solve(t_run: u32, dist_record: u64, v: u8) -> Option<WinningDuration> {
     // visualization: https://www.geogebra.org/calculator/zebs3ca6
     //
-    // dist(t_press) = v * t_press * (t_run - t_press)           || remove parenthesis
-    // dist(t_press) = v * t_press * t_run - t_press^2           || reorder to get squared part in front
-    // dist(t_press) = -t_press^2 + v * t_run * t_press          || note: `v` and `t_run` are constants, v being speed
+    // dist(t_press) = v * t_press * (t_run - t_press)
     //
-    // Subtracting the previous record shifts the parabola down
-    // so that its two zero-points are the min and max value we have to press to beat the record, so...
-    // rec(t_press) = dist_record
-    // opt(t_press) = dist(t_press) - rec(t_press)
-    // dist_shifted(t_press) = -t_press^2 + v * t_run * t_press - dist_record
+    // Beating the record means dist(t_press) > dist_record, or moving everything to one side:
+    // v * t_press^2 - v * t_run * t_press + dist_record < 0
     //
-    // solving with: x_1/2 = (-b +/- sqrt(b^2 - 4ac)) / (2a)
-    // with:
-    //   a = -1
-    //   b = v * t_run
-    //   c = -dist_record
+    // Records beyond i64 are out of reach of any race of up to u32::MAX ms, whose best distance is below 2^62 mm.
+    let v = i64::from(v);
+    let t_run = i64::from(t_run);
+    let dist_record = i64::try_from(dist_record).ok()?;
+    let presses = math::quadratic_below_zero(v, -v * t_run, dist_record)?;
+    // Winning presses lie strictly between 0 and `t_run`.
+    let press = |t: i64| u32::try_from(t).expect("press time to be within the race");
+    Some(WinningDuration {
+        min_press: press(*presses.start()),
+        max_press: press(*presses.end()),
+    })
+}
 
-    let b = u64::from(v) * t_run;
-    let term = b.pow(2) - 4 * dist_record;
-    let sqrt = f64::sqrt(term as f64);
+/// The race sheet, read both as individual races and as one long race with the spaces removed.
+#[derive(Debug)]
+pub struct Races {
+    games: Vec<Game>,
+    long_game: Game,
+}
 
-    let x_1 = (-(b as f64) + sqrt) / -2.0;
-    let x_2 = (-(b as f64) - sqrt) / -2.0;
+#[derive(Debug)]
+struct Game {
+    duration: u32,
+    distance_record: u64,
+}
 
-    assert!(x_1.is_sign_positive());
-    assert!(x_2.is_sign_positive());
+/// The values following `label` on `line`, a line of `input`.
+fn labeled_values<'a>(input: &'a str, line: Option<&'a str>, label: &str) -> Result<&'a str, ParseError> {
+    let line = line.ok_or_else(|| ParseError::end_of_input(input, format!("a `{label}` line")))?;
+    line.strip_prefix(label)
+        .ok_or_else(|| ParseError::new(input, line, format!("a `{label}` line")))
+}
 
-    WinningDuration {
-        min_press: x_1.floor() as u32 + 1,
-        max_press: x_2.ceil() as u32 - 1,
+/// Pair up the `times` and `distances`, which must have the same, non-zero number of values.
+fn parse_individual_games(input: &str, times: &str, distances: &str) -> Result<Vec<Game>, ParseError> {
+    let durations = parse::integers::<u32>(input, times.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+    if durations.is_empty() {
+        return Err(ParseError::new(input, times, "the time of at least one race"));
+    }
+    let mut records = parse::integers::<u64>(input, distances.as_bytes());
+    let games = durations
+        .iter()
+        .map(|&duration| {
+            let distance_record = records.next().ok_or_else(|| {
+                ParseError::new(
+                    input,
+                    &distances[distances.len()..],
+                    format!("as many distances as times ({})", durations.len()),
+                )
+            })??;
+            Ok(Game {
+                duration,
+                distance_record,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match records.remainder().trim_ascii() {
+        [] => Ok(games),
+        extra => Err(ParseError::at_bytes(
+            input,
+            extra,
+            format!("as many distances as times ({})", durations.len()),
+        )),
     }
 }
 
-#[derive(Debug)]
-struct Game {
-    duration: u64,
-    distance_record: u64,
+/// Read `values` as a single number, ignoring the spaces between its digits.
+fn parse_long_number<T: Integer>(input: &str, values: &str) -> Result<T, ParseError> {
+    values
+        .bytes()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .try_fold(None, |number: Option<T>, byte| match byte.wrapping_sub(b'0') {
+            digit @ 0..=9 => number.unwrap_or(T::ZERO).push_digit(digit, false).map(Some),
+            _ => None,
+        })
+        .flatten()
+        .ok_or_else(|| ParseError::new(input, values.trim(), "digits forming a single number"))
 }
 
-fn parse_individual_games(input: &str) -> impl Iterator<Item = Game> + '_ {
-    let mut lines = input.lines();
-    let times = lines
-        .next()
-        .expect("first line")
-        .trim_start_matches("Time:")
-        .split_ascii_whitespace()
-        .map(|it| it.parse::<u64>().expect("number"));
-    let distances = lines
-        .next()
-        .expect("second line")
-        .trim_start_matches("Distance:")
-        .split_ascii_whitespace()
-        .map(|it| it.parse::<u64>().expect("number"));
-    times.zip(distances).map(|(time, distance)| Game { duration: time, distance_record: distance })
-}
-
-fn parse_long_game(input: &str) -> Game {
-    let mut lines = input.lines();
-    let time = lines
-        .next()
-        .expect("first line")
-        .trim_start_matches("Time:")
-        .chars()
-        .filter(|c| !c.is_whitespace())
-        .collect::<String>()
-        .parse::<u64>()
-        .expect("number");
-    let distance = lines
-        .next()
-        .expect("second line")
-        .trim_start_matches("Distance:")
-        .chars()
-        .filter(|c| !c.is_whitespace())
-        .collect::<String>()
-        .parse::<u64>()
-        .expect("number");
-    Game { duration: time, distance_record: distance }
+/// Deliberately naive reference implementations, to check the closed-form solution against.
+#[cfg(any(test, feature = "oracle"))]
+pub mod oracle {
+    use super::{Game, Races};
+
+    pub fn part1(races: &Races) -> u32 {
+        races.games.iter().map(winning_presses).product()
+    }
+
+    pub fn part2(races: &Races) -> u32 {
+        winning_presses(&races.long_game)
+    }
+
+    /// Try every press time.
+    fn winning_presses(game: &Game) -> u32 {
+        let duration = u64::from(game.duration);
+        let wins = (0..=duration)
+            .filter(|press| press * (duration - press) > game.distance_record)
+            .count();
+        u32::try_from(wins).expect("count to be in u32 range")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{oracle, parse, part1, part2};
+
+    #[test]
+    fn test_unbeatable_records_have_no_options() {
+        // The best distances are 4 mm in 4 ms, 6 mm in 5 ms and 506 mm in the long race of 45 ms.
+        let races = parse("Time:      4    5\nDistance:  4  999\n").expect("valid input");
+        assert_eq!(part1(&races), 0);
+        assert_eq!(part2(&races), 0);
+        let races = parse("Time:      5\nDistance:  9999999999999999999\n").expect("valid input");
+        assert_eq!(part2(&races), 0);
+    }
+
+    #[test]
+    fn test_races_must_be_complete() {
+        for (input, found, expected) in [
+            ("Time:\nDistance:\n", "", "the time of at least one race"),
+            ("Time: 7 15\nDistance: 9\n", "", "as many distances as times (2)"),
+            ("Time: 7\nDistance: 9 40\n", "40", "as many distances as times (1)"),
+        ] {
+            let err = parse(input).expect_err("incomplete races");
+            assert_eq!(
+                (err.found.as_str(), err.expected.as_str()),
+                (found, expected),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_oracle_on_generated_inputs() {
+        let generator = crate::generate::generator(6).expect("generator");
+        for seed in 0..100 {
+            let input = generator.generate(seed, 2, None);
+            let races = parse(&input).expect("valid input");
+            assert_eq!(part1(&races), oracle::part1(&races), "seed {seed}");
+            assert_eq!(part2(&races), oracle::part2(&races), "seed {seed}");
+        }
+    }
 }
@@ -1,17 +1,44 @@
 use std::cmp::Ordering;
 
-pub fn part1(input: &str) -> u64 {
-    solve(input, false)
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+
+/// Parses all hands and their bids, reading each `J` as a Jack.
+pub fn parse(input: &str) -> Result<Vec<(Hand, u64)>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (card_identifiers, num) = line
+                .split_once(' ')
+                .ok_or_else(|| ParseError::new(input, &line[line.len()..], "a space between the hand and the bid"))?;
+            Ok((Hand::parse(input, card_identifiers)?, parse::number::<u64>(input, num)?))
+        })
+        .collect()
+}
+
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
 }
 
-pub fn part2(input: &str) -> u64 {
-    solve(input, true)
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
 }
 
-pub fn solve(input: &str, interpret_j_as_joker: bool) -> u64 {
-    let mut hands = parse(input, interpret_j_as_joker)
-        .map(|(hand, bid)| (hand, hand.strength(), bid))
-        .collect::<Vec<_>>();
+pub fn part1(hands: &[(Hand, u64)]) -> u64 {
+    solve(hands.iter().copied())
+}
+
+pub fn part2(hands: &[(Hand, u64)]) -> u64 {
+    solve(hands.iter().map(|(hand, bid)| (hand.with_jacks_as_jokers(), *bid)))
+}
+
+fn solve(hands: impl Iterator<Item = (Hand, u64)>) -> u64 {
+    let mut hands = tracing::debug_span!("strength").in_scope(|| {
+        hands
+            .map(|(hand, bid)| (hand, hand.strength(), bid))
+            .collect::<Vec<_>>()
+    });
+    let _span = tracing::debug_span!("rank").entered();
     hands.sort_by(|(hand_a, strength_a, _), (hand_b, strength_b, _)| {
         match strength_a.cmp(strength_b) {
             Ordering::Equal => hand_a
@@ -31,38 +58,40 @@ pub fn solve(input: &str, interpret_j_as_joker: bool) -> u64 {
         .sum()
 }
 
-fn parse(input: &str, interpret_j_as_joker: bool) -> impl Iterator<Item = (Hand, u64)> + '_ {
-    input
-        .lines()
-        .map(|line| line.split_once(' ').expect("line with at least one space"))
-        .map(move |(card_identifiers, num)| {
-            (
-                {
-                    let mut it = card_identifiers
-                        .chars()
-                        .map(|c| Card::try_from((c, interpret_j_as_joker)).expect("valid card identifier"));
-                    Hand {
-                        cards: [
-                            it.next().expect("first card"),
-                            it.next().expect("second card"),
-                            it.next().expect("third card"),
-                            it.next().expect("fourth card"),
-                            it.next().expect("fifth card"),
-                        ],
-                    }
-                },
-                num.parse::<u64>().expect("valid number"),
-            )
-        })
-}
-
 #[derive(Debug, Clone, Copy)]
-struct Hand {
+pub struct Hand {
     /// Order must be preserved!
     cards: [Card; 5],
 }
 
 impl Hand {
+    /// Parse the five `card_identifiers` of a hand, a part of `input`.
+    fn parse(input: &str, card_identifiers: &str) -> Result<Self, ParseError> {
+        if card_identifiers.chars().count() != 5 {
+            return Err(ParseError::new(input, card_identifiers, "a hand of five cards"));
+        }
+        let mut cards = [Card::Two; 5];
+        for ((i, c), card) in card_identifiers.char_indices().zip(&mut cards) {
+            *card = Card::try_from((c, false)).map_err(|_err| {
+                ParseError::new(
+                    input,
+                    &card_identifiers[i..i + c.len_utf8()],
+                    "a card (A, K, Q, J, T or 2 to 9)",
+                )
+            })?;
+        }
+        Ok(Self { cards })
+    }
+
+    fn with_jacks_as_jokers(self) -> Self {
+        Self {
+            cards: self.cards.map(|card| match card {
+                Card::Jack => Card::Joker,
+                other => other,
+            }),
+        }
+    }
+
     fn strength(self) -> HandStrength {
         let mut jokers = 0;
         let mut counts = [0u8; 13];
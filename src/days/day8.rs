@@ -1,22 +1,51 @@
 use petgraph::prelude::*;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-type Graph = StableGraph<(), Direction>;
+use crate::answer::Answer;
+use crate::geometry::Turn;
+use crate::math;
+use crate::parse::ParseError;
 
-pub fn part1(input: &str) -> u64 {
-    let (directions, nodes) = parse(input);
-    let (g, mapping) = build_graph(nodes);
+type Graph = StableGraph<(), Turn>;
 
-    let start = *mapping.get("AAA").expect("start");
-    let target = *mapping.get("ZZZ").expect("target");
-    count_steps_to_reach_first_target_node(&g, start, &[target], &directions.collect::<Vec<_>>())
+pub fn parse(input: &str) -> Result<Network<'_>, ParseError> {
+    let (directions, nodes) = read_input(input)?;
+    let (graph, mapping) = tracing::debug_span!("build_graph").in_scope(|| build_graph(input, nodes))?;
+    Ok(Network {
+        directions,
+        graph,
+        mapping,
+    })
 }
 
-pub fn part2(input: &str) -> u64 {
-    let (directions, nodes) = parse(input);
-    let (g, mapping) = build_graph(nodes);
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
+}
+
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
+}
+
+/// `None` if the network lacks `AAA` or `ZZZ`, like the example of part 2, or if `ZZZ` cannot be reached.
+pub fn part1(network: &Network<'_>) -> Option<u64> {
+    let Network {
+        directions,
+        graph: g,
+        mapping,
+    } = network;
+
+    let start = *mapping.get("AAA")?;
+    let target = *mapping.get("ZZZ")?;
+    count_steps_to_reach_first_target_node(g, start, &[target], directions)
+}
 
-    let directions = directions.collect::<Vec<_>>();
+/// `None` if a ghost can never reach a node ending in `Z`.
+pub fn part2(network: &Network<'_>) -> Option<u64> {
+    let Network {
+        directions,
+        graph: g,
+        mapping,
+    } = network;
 
     let target_nodes = mapping
         .keys()
@@ -30,34 +59,41 @@ pub fn part2(input: &str) -> u64 {
         .map(|k| *mapping.get(*k).expect("present"));
 
     starting_nodes
-        .map(|start| count_steps_to_reach_first_target_node(&g, start, &target_nodes, &directions))
-        .fold(1, lcm)
+        .map(|start| {
+            tracing::debug_span!("count_steps", start = start.index())
+                .in_scope(|| count_steps_to_reach_first_target_node(g, start, &target_nodes, directions))
+        })
+        .try_fold(1, |steps, ghost_steps| Some(math::lcm(steps, ghost_steps?)))
 }
 
+/// `None` if no target can be reached from `start`.
 fn count_steps_to_reach_first_target_node(
     g: &Graph,
     start: NodeIndex,
     targets: &[NodeIndex],
-    directions: &[Direction],
-) -> u64 {
+    directions: &[Turn],
+) -> Option<u64> {
+    // After visiting every node at every position in the directions, the walk only repeats itself.
+    let max_steps = g.node_count() * directions.len();
     let mut current = start;
-    let mut steps = 0;
-    for d in directions.iter().cycle() {
-        let target = g
+    for (steps, d) in (1..).zip(directions.iter().cycle().take(max_steps)) {
+        current = g
             .edges_directed(current, Outgoing)
             .find(|e| e.weight() == d)
-            .expect("edge")
+            .expect("parse to define both edges of every node")
             .target();
-        current = target;
-        steps += 1;
         if targets.contains(&current) {
-            break;
+            return Some(steps);
         }
     }
-    steps
+    None
 }
 
-fn build_graph<'a>(nodes: impl Iterator<Item = NodeWithEdges<'a>> + 'a) -> (Graph, BTreeMap<&'a str, NodeIndex>) {
+#[allow(clippy::type_complexity)]
+fn build_graph<'a>(
+    input: &'a str,
+    nodes: impl Iterator<Item = Result<NodeWithEdges<'a>, ParseError>> + 'a,
+) -> Result<(Graph, BTreeMap<&'a str, NodeIndex>), ParseError> {
     fn get_or_insert<'a>(node: &'a str, mapping: &mut BTreeMap<&'a str, NodeIndex>, g: &mut Graph) -> NodeIndex {
         match mapping.get(node) {
             Some(i) => *i,
@@ -70,20 +106,35 @@ fn build_graph<'a>(nodes: impl Iterator<Item = NodeWithEdges<'a>> + 'a) -> (Grap
     }
     let mut g: Graph = StableGraph::new();
     let mut mapping: BTreeMap<&str, NodeIndex> = BTreeMap::new();
-    for NodeWithEdges { source, left, right } in nodes {
+    let mut defined = BTreeSet::new();
+    for node in nodes {
+        let NodeWithEdges { source, left, right } = node?;
+        if !defined.insert(source) {
+            return Err(ParseError::new(input, source, "a node not defined before"));
+        }
         let i_source = get_or_insert(source, &mut mapping, &mut g);
         let i_left = get_or_insert(left, &mut mapping, &mut g);
         let i_right = get_or_insert(right, &mut mapping, &mut g);
-        g.add_edge(i_source, i_left, Direction::Left);
-        g.add_edge(i_source, i_right, Direction::Right);
+        g.add_edge(i_source, i_left, Turn::Left);
+        g.add_edge(i_source, i_right, Turn::Right);
+    }
+    // The keys are the first references to each node, report the first one in the input.
+    if let Some(undefined) = mapping
+        .keys()
+        .filter(|node| !defined.contains(*node))
+        .min_by_key(|node| node.as_ptr())
+    {
+        return Err(ParseError::new(input, undefined, "a node defined in the network"));
     }
-    (g, mapping)
+    Ok((g, mapping))
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Left,
-    Right,
+/// The parsed puzzle input: the directions to follow and the graph of nodes, which are looked up by name.
+#[derive(Debug)]
+pub struct Network<'a> {
+    directions: Vec<Turn>,
+    graph: Graph,
+    mapping: BTreeMap<&'a str, NodeIndex>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -93,63 +144,139 @@ struct NodeWithEdges<'a> {
     right: &'a str,
 }
 
-fn parse(
+/// Read the directions and lazily parse the nodes.
+#[allow(clippy::type_complexity)]
+fn read_input(
     input: &str,
-) -> (
-    impl Iterator<Item = Direction> + '_,
-    impl Iterator<Item = NodeWithEdges<'_>> + '_,
-) {
+) -> Result<
+    (
+        Vec<Turn>,
+        impl Iterator<Item = Result<NodeWithEdges<'_>, ParseError>> + '_,
+    ),
+    ParseError,
+> {
     let mut lines = input.lines();
-    let dirs = lines.next().expect("direction information").chars().map(|c| match c {
-        'L' => Direction::Left,
-        'R' => Direction::Right,
-        _ => panic!("Unexpected direction character"),
-    });
+    let first = lines
+        .next()
+        .ok_or_else(|| ParseError::end_of_input(input, "a line of directions"))?;
+    if first.is_empty() {
+        return Err(ParseError::new(input, first, "a direction (L or R)"));
+    }
+    let dirs = first
+        .char_indices()
+        .map(|(i, c)| match c {
+            'L' => Ok(Turn::Left),
+            'R' => Ok(Turn::Right),
+            _ => Err(ParseError::new(
+                input,
+                &first[i..i + c.len_utf8()],
+                "a direction (L or R)",
+            )),
+        })
+        .collect::<Result<_, _>>()?;
 
-    let nodes = lines.filter(|l| !l.is_empty()).map(|line| {
+    let nodes = lines.filter(|l| !l.is_empty()).map(move |line| {
         let (source, rest) = line
             .split_once('=')
             .map(|(a, b)| (a.trim_end(), b.trim_start()))
-            .expect("=");
+            .ok_or_else(|| ParseError::new(input, line, "`<node> = (<left>, <right>)`"))?;
         let (left, right) = rest
-            .trim_start_matches('(')
-            .trim_end_matches(')')
-            .split_once(',')
+            .strip_prefix('(')
+            .and_then(|rest| rest.strip_suffix(')'))
+            .and_then(|rest| rest.split_once(','))
             .map(|(a, b)| (a.trim(), b.trim()))
-            .expect(",");
-        NodeWithEdges { source, left, right }
+            .ok_or_else(|| ParseError::new(input, rest, "`(<left>, <right>)`"))?;
+        Ok(NodeWithEdges { source, left, right })
     });
 
-    (dirs, nodes)
+    Ok((dirs, nodes))
 }
 
-/// Least common multiple of two positive integers. Using `gcd`.
-fn lcm(a: u64, b: u64) -> u64 {
-    a * (b / gcd(a, b))
-}
+/// Deliberately naive reference implementations, to check the LCM shortcut against.
+#[cfg(any(test, feature = "oracle"))]
+pub mod oracle {
+    use petgraph::prelude::*;
+    use std::collections::BTreeSet;
+
+    use super::Network;
+
+    /// Move all ghosts at once until every one of them stands on a node ending in `Z`.
+    pub fn part2(network: &Network<'_>) -> u64 {
+        let Network {
+            directions,
+            graph: g,
+            mapping,
+        } = network;
+        let nodes_ending_in = |last: char| {
+            mapping
+                .iter()
+                .filter(move |(name, _)| name.ends_with(last))
+                .map(|(_, &node)| node)
+        };
+        let targets = nodes_ending_in('Z').collect::<BTreeSet<_>>();
+        let mut ghosts = nodes_ending_in('A').collect::<Vec<_>>();
 
-/// Greatest common divisor of two positive integers. Euclidean algorithm.
-fn gcd(a: u64, b: u64) -> u64 {
-    match (a, b) {
-        (a, 0) => a,
-        (a, b) => gcd(b, a % b),
+        let mut steps = 0;
+        for d in directions.iter().cycle() {
+            for ghost in &mut ghosts {
+                *ghost = g
+                    .edges_directed(*ghost, Outgoing)
+                    .find(|e| e.weight() == d)
+                    .expect("edge")
+                    .target();
+            }
+            steps += 1;
+            if ghosts.iter().all(|ghost| targets.contains(ghost)) {
+                break;
+            }
+        }
+        steps
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::gcd;
-    use super::lcm;
+    use super::oracle;
+    use super::parse;
+    use super::{part1, part2};
 
     #[test]
-    fn test_lcm() {
-        assert_eq!(lcm(21, 6), 42);
-        assert_eq!(lcm(6, 21), 42);
+    fn test_network_must_define_every_node_once() {
+        let err = parse("LR\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").expect_err("undefined node");
+        assert_eq!((err.line, err.column, err.found.as_str()), (3, 8, "BBB"));
+        let err = parse("LR\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)\n").expect_err("node defined twice");
+        assert_eq!((err.line, err.column, err.found.as_str()), (4, 1, "AAA"));
+        let err = parse("\n\nAAA = (AAA, AAA)\n").expect_err("no directions");
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (1, 1, "a direction (L or R)")
+        );
     }
 
     #[test]
-    fn test_gcd() {
-        assert_eq!(gcd(48, 18), 6);
-        assert_eq!(gcd(18, 48), 6);
+    fn test_no_way_from_start_to_target() {
+        let network = parse("LR\n\nXXX = (XXX, XXX)\n").expect("valid network");
+        assert_eq!(part1(&network), None);
+        let network = parse("LR\n\nAAA = (AAA, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)\n").expect("valid network");
+        assert_eq!(part1(&network), None);
+        assert_eq!(part2(&network), None);
+        let network = parse("L\n\nAAA = (ZZZ, AAA)\nZZZ = (ZZZ, ZZZ)\n").expect("valid network");
+        assert_eq!((part1(&network), part2(&network)), (Some(1), Some(1)));
+    }
+
+    #[test]
+    fn test_matches_oracle_on_generated_inputs() {
+        let generator = crate::generate::generator(8).expect("generator");
+        for seed in 0..20 {
+            for size in [10, 60, 150] {
+                let input = generator.generate(seed, size, None);
+                let network = parse(&input).expect("valid input");
+                assert_eq!(
+                    part2(&network),
+                    Some(oracle::part2(&network)),
+                    "seed {seed}, size {size}"
+                );
+            }
+        }
     }
 }
@@ -1,21 +1,36 @@
 use itertools::Itertools;
 use smallvec::SmallVec;
 
-pub fn part1(input: &str) -> i64 {
-    input
-        .lines()
-        .map(|line| {
-            let mut history: SmallVec<[i64; 32]> = read_history(line).collect();
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+
+pub fn parse(input: &str) -> Result<Vec<SmallVec<[i64; 32]>>, ParseError> {
+    input.lines().map(|line| read_history(input, line)).collect()
+}
+
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {
+    Ok(part1(&parse(input)?).into())
+}
+
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {
+    Ok(part2(&parse(input)?).into())
+}
+
+pub fn part1(histories: &[SmallVec<[i64; 32]>]) -> i64 {
+    histories
+        .iter()
+        .map(|history| {
+            let mut history = history.clone();
             estimate_next(&mut history)
         })
         .sum()
 }
 
-pub fn part2(input: &str) -> i64 {
-    input
-        .lines()
-        .map(|line| {
-            let mut history: SmallVec<[i64; 32]> = read_history(line).collect();
+pub fn part2(histories: &[SmallVec<[i64; 32]>]) -> i64 {
+    histories
+        .iter()
+        .map(|history| {
+            let mut history = history.clone();
             history.reverse();
             estimate_next(&mut history)
         })
@@ -34,11 +49,37 @@ fn estimate_next(history: &mut [i64]) -> i64 {
         slice = &mut history[0..upper_bound];
     }
 
-    history[upper_bound - 1..history.len()].iter().sum::<i64>()
+    // The differences end in zeros, or in no values at all for histories too short to reach zeros.
+    history[upper_bound..].iter().sum::<i64>()
 }
 
-fn read_history(input: &str) -> impl Iterator<Item = i64> + '_ {
-    input
-        .split_ascii_whitespace()
-        .map(|section| section.parse::<i64>().expect("number"))
+/// Read the values of one history, a `line` of `input`.
+fn read_history(input: &str, line: &str) -> Result<SmallVec<[i64; 32]>, ParseError> {
+    let history = parse::integers::<i64>(input, line.as_bytes()).collect::<Result<SmallVec<_>, _>>()?;
+    match history.is_empty() {
+        true => Err(ParseError::new(input, line, "a history of at least one value")),
+        false => Ok(history),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, part1, part2};
+
+    #[test]
+    fn test_empty_history_is_rejected() {
+        let err = parse("0 3 6\n  \n1 2").expect_err("empty history");
+        assert_eq!(
+            (err.line, err.expected.as_str()),
+            (2, "a history of at least one value")
+        );
+    }
+
+    #[test]
+    fn test_histories_too_short_to_reach_zeros() {
+        let histories = parse("5\n1 2\n1 4 9").expect("valid histories");
+        // 5 5 5, 0 1 2 3 and 0 1 4 9 16.
+        assert_eq!(part1(&histories), 5 + 3 + 16);
+        assert_eq!(part2(&histories), 5);
+    }
 }
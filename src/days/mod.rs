@@ -1,12 +1,57 @@
-pub mod day1;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
-pub mod day10;
-pub mod day11;
-pub mod day12;
+// The `parse` and `try_part*` functions of every day only fail on invalid input, with a `ParseError` describing it.
+#![allow(clippy::missing_errors_doc)]
+
+use crate::registry::days;
+
+days! {
+    1 day1 "Trebuchet?!" {
+        1 part1 ["part1_test", real],
+        2 part2 ["part2_test", real],
+    }
+    2 day2 "Cube Conundrum" {
+        1 part1 ["test", real],
+        2 part2 ["test", real],
+    }
+    3 day3 "Gear Ratios" visualize {
+        1 part1 ["test", real],
+        2 part2 ["test", real],
+    }
+    4 day4 "Scratchcards" {
+        1 part1 ["test", real],
+        2 part2 ["test", real],
+    }
+    5 day5 "If You Give A Seed A Fertilizer" {
+        1 part1 ["test", real],
+        2 part2 ["test", real],
+    }
+    6 day6 "Wait For It" {
+        1 part1 ["test", real],
+        2 part2 ["test", real],
+    }
+    7 day7 "Camel Cards" {
+        1 part1 ["test", "test2", real],
+        2 part2 ["test", "test2", real],
+    }
+    8 day8 "Haunted Wasteland" {
+        1 part1 ["part1_test", "part1_test2", real],
+        2 part2 ["part2_test", real],
+    }
+    9 day9 "Mirage Maintenance" {
+        1 part1 ["test", real],
+        2 part2 ["test", real],
+    }
+    10 day10 "Pipe Maze" visualize {
+        1 part1 ["part1_test1", "part1_test2", real],
+        2 part2 ["part1_test1", "part1_test2", "part2_test1", "part2_test2", "part2_test3", real],
+    }
+    11 day11 "Cosmic Expansion" visualize {
+        1 part1 ["test", real],
+        2 part2_expand_9 ["test"],
+        2 part2_expand_99 ["test"],
+        2 part2_expand_999_999 ["test", real],
+    }
+    12 day12 "Hot Springs" {
+        1 part1 ["part1_test1", real],
+        2 part2 ["part1_test1", real],
+    }
+}
@@ -0,0 +1,723 @@
+use std::{collections::BTreeSet, fmt::Write, ops::RangeInclusive};
+
+/// Generates random, but valid puzzle inputs of a day.
+#[derive(Debug, Clone, Copy)]
+pub struct Generator {
+    pub day: u8,
+    /// What the size parameter controls, e.g. `number of cards`.
+    pub size: &'static str,
+    /// Size of an input similar to the real one.
+    pub default_size: usize,
+    /// What the density parameter controls, if this generator uses it.
+    pub density: Option<&'static str>,
+    pub default_density: f64,
+    generate: fn(&mut Rng, usize, f64) -> String,
+}
+
+impl Generator {
+    /// Generate an input. The same `seed`, `size` and `density` always produce the same input.
+    /// Uses the default density if `density` is `None`.
+    pub fn generate(&self, seed: u64, size: usize, density: Option<f64>) -> String {
+        let density = density.unwrap_or(self.default_density).clamp(0.0, 1.0);
+        (self.generate)(&mut Rng::new(seed), size, density)
+    }
+}
+
+/// Generators of all days, ordered by day.
+pub static GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        size: "number of lines",
+        default_size: 1000,
+        density: None,
+        default_density: 0.0,
+        generate: day1,
+    },
+    Generator {
+        day: 2,
+        size: "number of games",
+        default_size: 100,
+        density: None,
+        default_density: 0.0,
+        generate: day2,
+    },
+    Generator {
+        day: 3,
+        size: "width and height of the schematic",
+        default_size: 140,
+        density: Some("chance of each tile being a symbol"),
+        default_density: 0.03,
+        generate: day3,
+    },
+    Generator {
+        day: 4,
+        size: "number of cards",
+        default_size: 200,
+        density: Some("chance of each winning number being one of ours"),
+        default_density: 0.1,
+        generate: day4,
+    },
+    Generator {
+        day: 5,
+        size: "number of mappings per stage",
+        default_size: 30,
+        density: None,
+        default_density: 0.0,
+        generate: day5,
+    },
+    Generator {
+        day: 6,
+        size: "number of races, at most 4",
+        default_size: 4,
+        density: None,
+        default_density: 0.0,
+        generate: day6,
+    },
+    Generator {
+        day: 7,
+        size: "number of hands",
+        default_size: 1000,
+        density: None,
+        default_density: 0.0,
+        generate: day7,
+    },
+    Generator {
+        day: 8,
+        size: "approximate number of nodes, at most 3000",
+        default_size: 750,
+        density: None,
+        default_density: 0.0,
+        generate: day8,
+    },
+    Generator {
+        day: 9,
+        size: "number of histories",
+        default_size: 200,
+        density: None,
+        default_density: 0.0,
+        generate: day9,
+    },
+    Generator {
+        day: 10,
+        size: "width and height of the maze",
+        default_size: 140,
+        density: Some("share of the maze enclosed by the loop"),
+        default_density: 0.5,
+        generate: day10,
+    },
+    Generator {
+        day: 11,
+        size: "width and height of the image",
+        default_size: 140,
+        density: Some("chance of each tile being a galaxy"),
+        default_density: 0.02,
+        generate: day11,
+    },
+    Generator {
+        day: 12,
+        size: "number of rows",
+        default_size: 1000,
+        density: Some("chance of each spring being unknown"),
+        default_density: 0.5,
+        generate: day12,
+    },
+];
+
+/// The generator of `day`, if any.
+pub fn generator(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|g| g.day == day)
+}
+
+/// Small and fast pseudo random number generator (`SplitMix64`).
+///
+/// Implemented here rather than taken from a crate, so that a seed keeps producing the same input across platforms
+/// and dependency updates.
+// Not `Copy`, as using a copy by accident would silently repeat random numbers.
+#[allow(missing_copy_implementations)]
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed number in `range`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range");
+        let span = u128::from(end - start) + 1;
+        start + ((u128::from(self.next_u64()) * span) >> 64) as u64
+    }
+
+    /// A uniformly distributed index below `n`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0..=(n as u64).saturating_sub(1)) as usize
+    }
+
+    /// `true` with probability `p`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    /// A random element of `items`, which must not be empty.
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const NONZERO_DIGITS: &[u8] = b"123456789";
+const DIGITS: &[u8] = b"0123456789";
+
+/// Calibration lines mixing letters, digits and spelled out digits, with at least one digit each.
+fn day1(rng: &mut Rng, lines: usize, _density: f64) -> String {
+    const WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+    let mut out = String::new();
+    for _ in 0..lines {
+        let tokens = rng.below(9);
+        let digit_at = rng.below(tokens + 1);
+        for i in 0..=tokens {
+            match (i == digit_at, rng.below(4)) {
+                (true, _) | (false, 0) => out.push(char::from(rng.pick(NONZERO_DIGITS))),
+                (false, 1) => out.push_str(rng.pick(&WORDS)),
+                (false, _) => out.push(char::from(rng.pick(LOWERCASE))),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Games of one to six draws, each showing one to twenty cubes of some colors.
+fn day2(rng: &mut Rng, games: usize, _density: f64) -> String {
+    let mut out = String::new();
+    for game in 1..=games {
+        let _ = write!(out, "Game {game}:");
+        for draw in 0..=rng.below(6) {
+            let mut colors = ["red", "green", "blue"];
+            rng.shuffle(&mut colors);
+            let shown = &colors[..=rng.below(3)];
+            for (i, color) in shown.iter().enumerate() {
+                let separator = match (draw, i) {
+                    (0, 0) => " ",
+                    (_, 0) => "; ",
+                    _ => ", ",
+                };
+                let _ = write!(out, "{separator}{} {color}", rng.range(1..=20));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// A square engine schematic with numbers of one to three digits, separated horizontally, and scattered symbols.
+fn day3(rng: &mut Rng, size: usize, symbol_density: f64) -> String {
+    const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+    let size = size.max(1);
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut x = 0;
+        while x < size {
+            if rng.chance(0.12) {
+                let len = usize::min(rng.below(3) + 1, size - x);
+                out.push(char::from(rng.pick(NONZERO_DIGITS)));
+                (1..len).for_each(|_| out.push(char::from(rng.pick(DIGITS))));
+                x += len;
+                if x < size {
+                    out.push('.');
+                    x += 1;
+                }
+            } else {
+                out.push(match rng.chance(symbol_density) {
+                    true => char::from(rng.pick(SYMBOLS)),
+                    false => '.',
+                });
+                x += 1;
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Cards of ten winning numbers and 25 of our numbers, never winning copies of cards past the last one.
+fn day4(rng: &mut Rng, cards: usize, match_chance: f64) -> String {
+    let mut out = String::new();
+    let mut pool = (1..=99).collect::<Vec<u8>>();
+    for id in 1..=cards {
+        rng.shuffle(&mut pool);
+        let (winning, others) = pool.split_at(10);
+        let matching = (0..10).filter(|_| rng.chance(match_chance)).count().min(cards - id);
+        let mut ours = winning[..matching]
+            .iter()
+            .chain(&others[..25 - matching])
+            .copied()
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut ours);
+
+        let _ = write!(out, "Card {id:>3}:");
+        for n in winning {
+            let _ = write!(out, " {n:>2}");
+        }
+        out.push_str(" |");
+        for n in &ours {
+            let _ = write!(out, " {n:>2}");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Five seed ranges and seven stages of mappings with non-overlapping source ranges.
+fn day5(rng: &mut Rng, mappings: usize, _density: f64) -> String {
+    const LIMIT: u64 = 1 << 32;
+    const CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    let mut out = String::from("seeds:");
+    for _ in 0..5 {
+        let len = rng.range(1..=LIMIT / 8);
+        let _ = write!(out, " {} {len}", rng.range(0..=LIMIT - len));
+    }
+    out.push('\n');
+
+    for stage in CATEGORIES.windows(2) {
+        let _ = write!(out, "\n{}-to-{} map:\n", stage[0], stage[1]);
+        let mut bounds = BTreeSet::new();
+        while bounds.len() < 2 * mappings.max(1) {
+            bounds.insert(rng.range(0..=LIMIT));
+        }
+        let bounds = bounds.into_iter().collect::<Vec<_>>();
+        let mut sources = bounds.chunks_exact(2).map(|b| (b[0], b[1] - b[0])).collect::<Vec<_>>();
+        rng.shuffle(&mut sources);
+        for (source, len) in sources {
+            let _ = writeln!(out, "{} {source} {len}", rng.range(0..=LIMIT - len));
+        }
+    }
+    out
+}
+
+/// Up to four races with two digit durations, each of which can be won, as can the long race they form together.
+fn day6(rng: &mut Rng, races: usize, _density: f64) -> String {
+    /// Longest distance reachable in a race of `time` milliseconds.
+    const fn best(time: u64) -> u64 {
+        (time / 2) * (time - time / 2)
+    }
+    fn concat(values: &[u64]) -> u64 {
+        values.iter().fold(0, |acc, v| acc * 10u64.pow(v.ilog10() + 1) + v)
+    }
+
+    let races = races.clamp(1, 4);
+    loop {
+        let times = (0..races).map(|_| rng.range(10..=99)).collect::<Vec<_>>();
+        let distances = times.iter().map(|&t| rng.range(1..=best(t) - 1)).collect::<Vec<_>>();
+        let (time, distance) = (concat(&times), concat(&distances));
+        if distance < best(time) {
+            let mut out = String::from("Time:    ");
+            for t in &times {
+                let _ = write!(out, " {t:>6}");
+            }
+            out.push_str("\nDistance:");
+            for d in &distances {
+                let _ = write!(out, " {d:>6}");
+            }
+            out.push('\n');
+            return out;
+        }
+    }
+}
+
+/// Hands of five distinct cards with bids up to 1000. No hand occurs twice, so that their order is well-defined.
+fn day7(rng: &mut Rng, hands: usize, _density: f64) -> String {
+    const CARDS: &[u8] = b"23456789TJQKA";
+    let mut seen = BTreeSet::new();
+    let mut out = String::new();
+    while seen.len() < hands.min(13usize.pow(5)) {
+        let hand = (0..5).map(|_| char::from(rng.pick(CARDS))).collect::<String>();
+        if seen.insert(hand.clone()) {
+            let _ = writeln!(out, "{hand} {}", rng.range(1..=1000));
+        }
+    }
+    out
+}
+
+/// A network of up to six ghosts walking separate cycles of distinct prime lengths, the first from `AAA` to `ZZZ`.
+/// Both directions of a node lead to the same node, so the answer does not depend on the directions.
+fn day8(rng: &mut Rng, nodes: usize, _density: f64) -> String {
+    fn next_prime(mut n: usize) -> usize {
+        while n < 2 || (2..n).take_while(|d| d * d <= n).any(|d| n.is_multiple_of(d)) {
+            n += 1;
+        }
+        n
+    }
+
+    let nodes = nodes.clamp(6, 3000);
+    let ghosts = (nodes / 50).clamp(1, 6);
+    let target = nodes / ghosts - 1;
+    let mut lengths = BTreeSet::new();
+    while lengths.len() < ghosts {
+        lengths.insert(next_prime(target / 2 + rng.below(target)));
+    }
+    let mut lengths = lengths.into_iter().collect::<Vec<_>>();
+    rng.shuffle(&mut lengths);
+
+    // Names of the nodes between start and end, neither ending in `A` nor in `Z`.
+    let mut names = Vec::new();
+    for a in UPPERCASE {
+        for b in UPPERCASE {
+            for &c in &UPPERCASE[1..25] {
+                names.push(String::from_utf8(vec![*a, *b, c]).expect("ASCII"));
+            }
+        }
+    }
+    rng.shuffle(&mut names);
+    let mut names = names.into_iter();
+    let mut terminals = BTreeSet::from([String::from("AAA"), String::from("ZZZ")]);
+    let mut terminal = |last: u8| loop {
+        let name = String::from_utf8(vec![rng.pick(UPPERCASE), rng.pick(UPPERCASE), last]).expect("ASCII");
+        if terminals.insert(name.clone()) {
+            return name;
+        }
+    };
+
+    let mut lines = Vec::new();
+    for (ghost, &length) in lengths.iter().enumerate() {
+        let (start, end) = match ghost {
+            0 => (String::from("AAA"), String::from("ZZZ")),
+            _ => (terminal(b'A'), terminal(b'Z')),
+        };
+        let path = names.by_ref().take(length - 1).collect::<Vec<_>>();
+        let mut previous = &start;
+        for node in path.iter().chain([&end]) {
+            lines.push(format!("{previous} = ({node}, {node})"));
+            previous = node;
+        }
+        lines.push(format!("{end} = ({first}, {first})", first = path[0]));
+    }
+    rng.shuffle(&mut lines);
+
+    let mut out = (0..rng.range(5..=300))
+        .map(|_| match rng.chance(0.5) {
+            true => 'L',
+            false => 'R',
+        })
+        .collect::<String>();
+    out.push_str("\n\n");
+    for line in &lines {
+        let _ = writeln!(out, "{line}");
+    }
+    out
+}
+
+/// Histories of 21 values of polynomials of degree up to five.
+#[allow(clippy::cast_possible_wrap)]
+fn day9(rng: &mut Rng, histories: usize, _density: f64) -> String {
+    let mut out = String::new();
+    for _ in 0..histories {
+        let coefficients = (0..=rng.below(6))
+            .map(|_| rng.range(0..=18) as i64 - 9)
+            .collect::<Vec<_>>();
+        let values = (0..21).map(|x: i64| coefficients.iter().rev().fold(0, |acc, c| acc * x + c));
+        let values = values.map(|v| v.to_string()).collect::<Vec<_>>();
+        let _ = writeln!(out, "{}", values.join(" "));
+    }
+    out
+}
+
+// Directions a tile of a pipe maze connects to, combined as bits.
+const NORTH: u8 = 1;
+const EAST: u8 = 2;
+const SOUTH: u8 = 4;
+const WEST: u8 = 8;
+
+/// A square maze with a single loop through the start tile, surrounded by unconnected pipes.
+///
+/// The loop is the outline of a random simply connected shape grown on a grid of half the resolution, which never
+/// touches the border of the maze.
+fn day10(rng: &mut Rng, size: usize, enclosed: f64) -> String {
+    let cells = (size.max(7) - 1) / 2;
+    let size = 2 * cells + 1;
+    let shape = grow_shape(rng, cells, enclosed);
+    let connections = trace_outline(&shape, cells);
+
+    let mut tiles = connections
+        .iter()
+        .map(|&c| match c {
+            c if c == NORTH | SOUTH => b'|',
+            c if c == EAST | WEST => b'-',
+            c if c == NORTH | EAST => b'L',
+            c if c == NORTH | WEST => b'J',
+            c if c == SOUTH | WEST => b'7',
+            c if c == SOUTH | EAST => b'F',
+            _ => match rng.chance(0.5) {
+                true => b'.',
+                false => rng.pick(b"|-LJ7F"),
+            },
+        })
+        .collect::<Vec<_>>();
+
+    // Replace a random tile of the loop with the start, and make sure that only its two loop neighbors face it.
+    let on_loop = (0..size * size).filter(|&i| connections[i] != 0).collect::<Vec<_>>();
+    let start = rng.pick(&on_loop);
+    tiles[start] = b'S';
+    let (y, x) = (start / size, start % size);
+    for (direction, neighbor, facing) in [
+        (NORTH, (y - 1, x), b"|7F"),
+        (EAST, (y, x + 1), b"-J7"),
+        (SOUTH, (y + 1, x), b"|LJ"),
+        (WEST, (y, x - 1), b"-LF"),
+    ] {
+        let neighbor = neighbor.0 * size + neighbor.1;
+        if connections[start] & direction == 0 && facing.contains(&tiles[neighbor]) {
+            tiles[neighbor] = b'.';
+        }
+    }
+
+    let mut out = String::new();
+    for row in tiles.chunks_exact(size) {
+        out.push_str(std::str::from_utf8(row).expect("ASCII"));
+        out.push('\n');
+    }
+    out
+}
+
+/// Grow a random shape on a square grid of `cells`, keeping off its border, until it covers about the share `enclosed`
+/// of the grid. Rejects cells that would enclose a hole or touch the shape only diagonally, so that its outline is a
+/// single loop.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn grow_shape(rng: &mut Rng, cells: usize, enclosed: f64) -> Vec<bool> {
+    let inside = |y: usize, x: usize| (1..cells - 1).contains(&y) && (1..cells - 1).contains(&x);
+    let mut shape = vec![false; cells * cells];
+    let start = (1 + rng.below(cells - 2), 1 + rng.below(cells - 2));
+    shape[start.0 * cells + start.1] = true;
+    let target = ((cells - 2).pow(2) as f64 * enclosed).max(1.0) as usize;
+    let mut frontier = vec![start];
+    let mut grown = 1;
+    let mut attempts = 0;
+    while grown < target && attempts < 20 * cells * cells {
+        attempts += 1;
+        let Some(&(y, x)) = frontier.get(rng.below(frontier.len())) else {
+            break;
+        };
+        let neighbors = [(y - 1, x), (y, x + 1), (y + 1, x), (y, x - 1)];
+        let candidates = neighbors
+            .into_iter()
+            .filter(|&(y, x)| inside(y, x) && !shape[y * cells + x])
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            frontier.retain(|&cell| cell != (y, x));
+            continue;
+        }
+        let (cy, cx) = rng.pick(&candidates);
+        shape[cy * cells + cx] = true;
+        if touches_diagonally(&shape, cells, cy, cx) || has_hole(&shape, cells) {
+            shape[cy * cells + cx] = false;
+            continue;
+        }
+        frontier.push((cy, cx));
+        grown += 1;
+    }
+    shape
+}
+
+/// Connections of the tiles on the outline of `shape`, at the corners and edges of its cells, at twice its resolution.
+fn trace_outline(shape: &[bool], cells: usize) -> Vec<u8> {
+    let size = 2 * cells + 1;
+    let mut connections = vec![0u8; size * size];
+    let mut connect = |from: (usize, usize), to: (usize, usize)| {
+        let (a, b) = match from.0 == to.0 {
+            true => (EAST, WEST),
+            false => (SOUTH, NORTH),
+        };
+        connections[from.0 * size + from.1] |= a;
+        connections[to.0 * size + to.1] |= b;
+    };
+    for y in 0..cells {
+        for x in 0..cells {
+            if !shape[y * cells + x] {
+                continue;
+            }
+            let (top, left) = (2 * y, 2 * x);
+            let sides = [
+                (
+                    !shape[(y - 1) * cells + x],
+                    [(top, left), (top, left + 1), (top, left + 2)],
+                ),
+                (
+                    !shape[(y + 1) * cells + x],
+                    [(top + 2, left), (top + 2, left + 1), (top + 2, left + 2)],
+                ),
+                (
+                    !shape[y * cells + x - 1],
+                    [(top, left), (top + 1, left), (top + 2, left)],
+                ),
+                (
+                    !shape[y * cells + x + 1],
+                    [(top, left + 2), (top + 1, left + 2), (top + 2, left + 2)],
+                ),
+            ];
+            for (_, [a, b, c]) in sides.into_iter().filter(|(outline, _)| *outline) {
+                connect(a, b);
+                connect(b, c);
+            }
+        }
+    }
+    connections
+}
+
+/// Whether the cell at `y`, `x` of `shape` forms a 2x2 block with another cell that only touches it diagonally.
+fn touches_diagonally(shape: &[bool], cells: usize, y: usize, x: usize) -> bool {
+    [(y - 1, x - 1), (y - 1, x), (y, x - 1), (y, x)]
+        .into_iter()
+        .any(|(top, left)| {
+            let cell = |y: usize, x: usize| shape[y * cells + x];
+            let (top_left, top_right) = (cell(top, left), cell(top, left + 1));
+            let (bottom_left, bottom_right) = (cell(top + 1, left), cell(top + 1, left + 1));
+            (top_left && bottom_right && !top_right && !bottom_left)
+                || (top_right && bottom_left && !top_left && !bottom_right)
+        })
+}
+
+/// Whether some cell outside of `shape` is enclosed by it, i.e. not connected to the border.
+fn has_hole(shape: &[bool], cells: usize) -> bool {
+    let mut reached = vec![false; cells * cells];
+    let mut stack = vec![(0usize, 0usize)];
+    reached[0] = true;
+    let mut count = 1;
+    while let Some((y, x)) = stack.pop() {
+        let neighbors = [(y.wrapping_sub(1), x), (y, x + 1), (y + 1, x), (y, x.wrapping_sub(1))];
+        for (y, x) in neighbors {
+            if y < cells && x < cells && !shape[y * cells + x] && !reached[y * cells + x] {
+                reached[y * cells + x] = true;
+                count += 1;
+                stack.push((y, x));
+            }
+        }
+    }
+    count + shape.iter().filter(|&&cell| cell).count() < cells * cells
+}
+
+/// A square image of scattered galaxies, with some rows and columns left empty.
+fn day11(rng: &mut Rng, size: usize, galaxy_density: f64) -> String {
+    let size = size.max(1);
+    let empty_rows = (0..size).map(|_| rng.chance(0.05)).collect::<Vec<_>>();
+    let empty_columns = (0..size).map(|_| rng.chance(0.05)).collect::<Vec<_>>();
+    let mut out = String::new();
+    for empty_row in empty_rows {
+        for &empty_column in &empty_columns {
+            out.push(match !empty_row && !empty_column && rng.chance(galaxy_density) {
+                true => '#',
+                false => '.',
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Rows of one to six groups of damaged springs, with some springs replaced by unknown ones.
+fn day12(rng: &mut Rng, rows: usize, unknown_density: f64) -> String {
+    let mut out = String::new();
+    for _ in 0..rows {
+        let groups = (0..=rng.below(6)).map(|_| rng.range(1..=5)).collect::<Vec<_>>();
+        let mut springs = ".".repeat(rng.below(3));
+        for (i, &group) in groups.iter().enumerate() {
+            if i > 0 {
+                springs.push_str(&".".repeat(1 + rng.below(3)));
+            }
+            (0..group).for_each(|_| springs.push('#'));
+        }
+        springs.push_str(&".".repeat(rng.below(3)));
+
+        let springs = springs
+            .chars()
+            .map(|spring| match rng.chance(unknown_density) {
+                true => '?',
+                false => spring,
+            })
+            .collect::<String>();
+        let groups = groups.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let _ = writeln!(out, "{springs} {}", groups.join(","));
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{generator, Rng, GENERATORS};
+
+    #[test]
+    fn test_every_day_has_a_generator() {
+        for day in crate::days::DAYS {
+            assert!(generator(day.day).is_some(), "no generator for day {}", day.day);
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_are_solvable() {
+        for generator in GENERATORS {
+            let day = crate::registry::day(generator.day).expect("registered day");
+            for seed in 0..8 {
+                for size in [1, 10, 50] {
+                    let input = generator.generate(seed, size, None);
+                    for solution in day.solutions {
+                        let answer = (solution.solve)(&input);
+                        assert!(
+                            answer.is_ok(),
+                            "day {} seed {seed} size {size}: {}",
+                            generator.day,
+                            answer.expect_err("invalid")
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_input() {
+        let generator = generator(10).expect("generator");
+        assert_eq!(generator.generate(7, 30, None), generator.generate(7, 30, None));
+        assert_ne!(generator.generate(7, 30, None), generator.generate(8, 30, None));
+    }
+
+    #[test]
+    fn test_rng_range() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!((3..=5).contains(&rng.range(3..=5)));
+        }
+        assert_eq!(rng.range(u64::MAX..=u64::MAX), u64::MAX);
+    }
+}
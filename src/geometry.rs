@@ -0,0 +1,214 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// A point, or a vector, in the plane. `x` grows to the east and `y` to the south, like the columns and rows of a
+/// `Grid`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Point<T> {
+    /// Number of orthogonal steps between both points.
+    pub fn manhattan_distance(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// Number of steps between both points, moving diagonally as well.
+    pub fn chebyshev_distance(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+}
+
+/// `|a - b|`, also for unsigned `T`.
+fn abs_diff<T: Copy + Ord + Sub<Output = T>>(a: T, b: T) -> T {
+    a.max(b) - a.min(b)
+}
+
+impl Point<usize> {
+    /// The point `offset` away from this one, if it does not lie above or left of the origin.
+    pub const fn checked_add_signed(self, offset: Point<isize>) -> Option<Self> {
+        match (self.x.checked_add_signed(offset.x), self.y.checked_add_signed(offset.y)) {
+            (Some(x), Some(y)) => Some(Self { x, y }),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A direction on the compass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+/// A change of direction by 90 degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// All directions, clockwise starting north.
+    pub const ALL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    #[must_use]
+    pub const fn turn_left(self) -> Self {
+        match self {
+            Self::North => Self::West,
+            Self::East => Self::North,
+            Self::South => Self::East,
+            Self::West => Self::South,
+        }
+    }
+
+    #[must_use]
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+        }
+    }
+
+    #[must_use]
+    pub const fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+
+    /// One step in this direction.
+    pub const fn vector(self) -> Point<isize> {
+        match self {
+            Self::North => Point::new(0, -1),
+            Self::East => Point::new(1, 0),
+            Self::South => Point::new(0, 1),
+            Self::West => Point::new(-1, 0),
+        }
+    }
+}
+
+impl From<Direction> for Point<isize> {
+    fn from(direction: Direction) -> Self {
+        direction.vector()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Direction, Point, Turn};
+
+    #[test]
+    fn test_turns() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn(Turn::Right).turn(Turn::Right), direction.opposite());
+            assert_eq!(direction.vector() + direction.opposite().vector(), Point::default());
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Point::from(Direction::West), Point::new(-1, 0));
+    }
+
+    #[test]
+    fn test_arithmetic_and_distances() {
+        let (a, b) = (Point::new(1, -2), Point::new(-3, 4));
+        assert_eq!(a + b, Point::new(-2, 2));
+        assert_eq!(a - b, Point::new(4, -6));
+        assert_eq!(-a * 2, Point::new(-2, 4));
+        assert_eq!(a.manhattan_distance(b), 10);
+        assert_eq!(a.chebyshev_distance(b), 6);
+        assert_eq!(Point::new(5u32, 0).manhattan_distance(Point::new(2, 3)), 6);
+    }
+
+    #[test]
+    fn test_checked_add_signed() {
+        let corner = Point::new(0usize, 0);
+        assert_eq!(corner.checked_add_signed(Direction::North.vector()), None);
+        assert_eq!(corner.checked_add_signed(Direction::West.vector()), None);
+        assert_eq!(
+            corner.checked_add_signed(Direction::South.vector()),
+            Some(Point::new(0, 1))
+        );
+        assert_eq!(
+            Point::new(2usize, 1).checked_add_signed(Point::new(-2, 3)),
+            Some(Point::new(0, 4))
+        );
+    }
+}
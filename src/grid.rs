@@ -0,0 +1,331 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::geometry::{Direction, Point};
+use crate::parse::ParseError;
+
+/// Steps to all 8 neighbors including the diagonal ones, clockwise starting north.
+const ALL_NEIGHBORS: [Point<isize>; 8] = [
+    Point::new(0, -1),
+    Point::new(1, -1),
+    Point::new(1, 0),
+    Point::new(1, 1),
+    Point::new(0, 1),
+    Point::new(-1, 1),
+    Point::new(-1, 0),
+    Point::new(-1, -1),
+];
+
+/// A rectangular map of cells, e.g. the tiles of a puzzle input, stored row by row and indexed by a `Point` with `x` as
+/// column and `y` as row, counted from the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// A grid of `width` columns, holding `cells` row by row.
+    ///
+    /// # Panics
+    ///
+    /// If the number of cells is not a multiple of `width`.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        let height = match width {
+            0 => 0,
+            _ => cells.len() / width,
+        };
+        assert_eq!(width * height, cells.len(), "cells must fill all rows");
+        Self { cells, width, height }
+    }
+
+    /// Parse a map of characters, one row per line, converting each character with `cell`.
+    ///
+    /// An empty input is an empty grid.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `cell` returns `None` for a character, reporting that `expected` was expected instead, if a line is
+    /// empty or if the lines differ in length.
+    pub fn parse(input: &str, expected: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        let width = input.lines().next().map_or(0, |line| line.chars().count());
+        if width == 0 && !input.is_empty() {
+            return Err(ParseError::new(input, &input[..0], expected));
+        }
+        let mut cells = Vec::with_capacity(input.len());
+        for line in input.lines() {
+            let row_start = cells.len();
+            let rest = match line.is_ascii() {
+                // Much faster than decoding the characters of the line.
+                true => {
+                    for (i, &byte) in line.as_bytes().iter().enumerate().take(width) {
+                        match cell(char::from(byte)) {
+                            Some(cell) => cells.push(cell),
+                            None => return Err(ParseError::new(input, &line[i..=i], expected)),
+                        }
+                    }
+                    &line[width.min(line.len())..]
+                }
+                false => {
+                    let mut chars = line.char_indices();
+                    for (i, c) in chars.by_ref().take(width) {
+                        match cell(c) {
+                            Some(cell) => cells.push(cell),
+                            None => return Err(ParseError::new(input, &line[i..i + c.len_utf8()], expected)),
+                        }
+                    }
+                    chars.next().map_or_else(|| &line[line.len()..], |(i, _)| &line[i..])
+                }
+            };
+            if cells.len() - row_start != width || !rest.is_empty() {
+                return Err(ParseError::new(
+                    input,
+                    rest,
+                    format!("every line to be {width} characters wide"),
+                ));
+            }
+        }
+        Ok(Self::from_vec(width, cells))
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub const fn contains(&self, pos: Point<usize>) -> bool {
+        pos.y < self.height && pos.x < self.width
+    }
+
+    pub fn get(&self, pos: Point<usize>) -> Option<&T> {
+        match self.contains(pos) {
+            true => Some(&self.cells[pos.y * self.width + pos.x]),
+            false => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Point<usize>) -> Option<&mut T> {
+        match self.contains(pos) {
+            true => Some(&mut self.cells[pos.y * self.width + pos.x]),
+            false => None,
+        }
+    }
+
+    /// The position `offset` away from `pos`, e.g. one step towards a `Direction`, if it lies inside the grid.
+    pub fn step(&self, pos: Point<usize>, offset: impl Into<Point<isize>>) -> Option<Point<usize>> {
+        pos.checked_add_signed(offset.into())
+            .filter(|&next| self.contains(next))
+    }
+
+    /// The orthogonal neighbors of `pos` inside the grid, clockwise starting north.
+    pub fn neighbors(&self, pos: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |offset| self.step(pos, offset))
+    }
+
+    /// The neighbors of `pos` inside the grid including the diagonal ones, clockwise starting north.
+    pub fn all_neighbors(&self, pos: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        ALL_NEIGHBORS
+            .into_iter()
+            .filter_map(move |offset| self.step(pos, offset))
+    }
+
+    /// The positions reached by repeatedly stepping `offset` from `pos` until leaving the grid, excluding `pos`.
+    pub fn ray(&self, pos: Point<usize>, offset: impl Into<Point<isize>>) -> impl Iterator<Item = Point<usize>> + '_ {
+        let offset = offset.into();
+        std::iter::successors(self.step(pos, offset), move |&pos| self.step(pos, offset))
+    }
+
+    /// The cells of row `row`, from left to right.
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    /// The cells of column `col`, from top to bottom.
+    ///
+    /// # Panics
+    ///
+    /// If `col` is not a column of the grid.
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        assert!(col < self.width, "column {col} out of bounds");
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    /// All rows, from top to bottom.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[T]> + DoubleEndedIterator + '_ {
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    /// All columns, from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Point<usize>> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| Point::new(x, y)))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> + '_ {
+        let mut next = Point::new(0, 0);
+        self.cells.iter().map(move |cell| {
+            // Cheaper than dividing the index by the width.
+            let pos = next;
+            next.x += 1;
+            if next.x == self.width {
+                next = Point::new(0, next.y + 1);
+            }
+            (pos, cell)
+        })
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point<usize>, &mut T)> + '_ {
+        let width = self.width;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, cell)| (Point::new(i % width, i / width), cell))
+    }
+
+    /// Position of the first cell matching `predicate`, row by row.
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Point<usize>> {
+        let i = self.cells.iter().position(predicate)?;
+        Some(Point::new(i % self.width, i / self.width))
+    }
+
+    /// A grid of the same size, holding the result of `f` for every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid of `width` by `height` cells, all set to `value`.
+    pub fn from_elem(width: usize, height: usize, value: T) -> Self {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Point<usize>) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos} out of bounds of {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, pos: Point<usize>) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos} out of bounds of {width}x{height} grid"))
+    }
+}
+
+/// Writes every row on its own line, so that a grid parsed from a map of characters displays as that map again.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Grid;
+    use crate::geometry::{Direction, Point};
+
+    const MAP: &str = "#..\n.#.\n..#\n#.#\n";
+
+    fn grid() -> Grid<char> {
+        Grid::parse(MAP, "# or .", |c| matches!(c, '#' | '.').then_some(c)).expect("valid map")
+    }
+
+    #[test]
+    fn test_parse_and_display_round_trip() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert_eq!(grid[Point::new(0, 3)], '#');
+        assert_eq!(grid.to_string(), MAP);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |input| Grid::parse(input, "# or .", |c| matches!(c, '#' | '.').then_some(c));
+        let err = parse("#.\n#x\n").expect_err("invalid cell");
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 2, "# or ."));
+        let err = parse("#.\n#..\n").expect_err("long line");
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 3, "."));
+        let err = parse("#.\n#\n").expect_err("short line");
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 2, ""));
+        let err = parse("\n\n").expect_err("empty rows");
+        assert_eq!((err.line, err.column, err.expected.as_str()), (1, 1, "# or ."));
+        assert!(parse("").expect("empty grid").is_empty());
+    }
+
+    #[test]
+    fn test_neighbors_are_checked() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbors(Point::new(0, 0)).collect::<Vec<_>>(),
+            [Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(
+            grid.all_neighbors(Point::new(2, 3)).collect::<Vec<_>>(),
+            [Point::new(2, 2), Point::new(1, 3), Point::new(1, 2)]
+        );
+        assert_eq!(grid.all_neighbors(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.step(Point::new(2, 3), Direction::East), None);
+        assert_eq!(grid.step(Point::new(2, 3), Direction::South), None);
+        assert_eq!(grid.get(Point::new(0, 4)), None);
+    }
+
+    #[test]
+    fn test_rays_rows_and_columns() {
+        let grid = grid();
+        let diagonal = grid.ray(Point::new(0, 0), Point::new(1, 1)).map(|pos| grid[pos]);
+        assert_eq!(diagonal.collect::<String>(), "##");
+        assert_eq!(grid.ray(Point::new(2, 0), Direction::East).count(), 0);
+        assert_eq!(grid.row(3), ['#', '.', '#']);
+        assert_eq!(grid.column(2).rev().collect::<String>(), "##..");
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.filter(|&&c| c == '#').count())
+                .collect::<Vec<_>>(),
+            [2, 1, 2]
+        );
+        assert_eq!(grid.rows().len(), 4);
+        assert_eq!(grid.position(|&c| c == '#'), Some(Point::new(0, 0)));
+        assert_eq!(
+            grid.iter().filter(|(_, &c)| c == '#').nth(1).map(|(pos, _)| pos),
+            Some(Point::new(1, 1))
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable overriding the directory puzzle inputs are read from.
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+/// Identifies one puzzle input of a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputId {
+    pub day: u8,
+    /// `None` for the real puzzle input, the name of the example otherwise (e.g. `"part1_test"`).
+    pub variant: Option<&'static str>,
+}
+
+impl InputId {
+    /// The real puzzle input of `day`, stored as `day{day}.txt`.
+    pub const fn real(day: u8) -> Self {
+        Self { day, variant: None }
+    }
+
+    /// An example input of `day` taken from the puzzle description, stored as `day{day}_{variant}.txt`.
+    pub const fn test(day: u8, variant: &'static str) -> Self {
+        Self {
+            day,
+            variant: Some(variant),
+        }
+    }
+
+    pub const fn is_test(self) -> bool {
+        self.variant.is_some()
+    }
+
+    pub fn file_name(self) -> String {
+        match self.variant {
+            None => format!("day{}.txt", self.day),
+            Some(variant) => format!("day{}_{variant}.txt", self.day),
+        }
+    }
+}
+
+impl Display for InputId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.variant.unwrap_or("real"))
+    }
+}
+
+/// Reads puzzle inputs from a directory at runtime.
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    dir: PathBuf,
+}
+
+impl Inputs {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Use the directory named by `AOC_INPUT_DIR` or, if not set, the `res` directory of this repository.
+    pub fn from_env() -> Self {
+        match std::env::var_os(INPUT_DIR_ENV) {
+            Some(dir) => Self::new(dir),
+            None => Self::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("res")),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, id: InputId) -> PathBuf {
+        self.dir.join(id.file_name())
+    }
+
+    /// Read the input identified by `id`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `std::io::ErrorKind::NotFound` if the file does not exist.
+    pub fn load(&self, id: InputId) -> std::io::Result<String> {
+        std::fs::read_to_string(self.path(id))
+    }
+}
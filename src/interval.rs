@@ -0,0 +1,346 @@
+use std::{
+    iter::Sum,
+    ops::{Add, Range, Sub},
+};
+
+/// The half-open interval `start..end`. Empty if `start >= end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    /// Exclusive.
+    pub end: T,
+}
+
+impl<T: Copy + Ord> Interval<T> {
+    pub const fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub const fn range(&self) -> Range<T> {
+        self.start..self.end
+    }
+
+    /// Whether both intervals share at least one value.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The values in both intervals, `None` if there are none.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Some(Self::new(self.start.max(other.start), self.end.min(other.end))).filter(|it| !it.is_empty())
+    }
+
+    /// The values in either interval, `None` if that is not a single interval, i.e. if there is a gap between them.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        match (self.is_empty(), other.is_empty()) {
+            (true, _) => Some(*other),
+            (_, true) => Some(*self),
+            _ if self.start <= other.end && other.start <= self.end => {
+                Some(Self::new(self.start.min(other.start), self.end.max(other.end)))
+            }
+            _ => None,
+        }
+    }
+
+    /// The values of `self` not in `other`: the parts left and right of `other`, each `None` if empty.
+    pub fn difference(&self, other: &Self) -> (Option<Self>, Option<Self>) {
+        if other.is_empty() {
+            return (Some(*self).filter(|it| !it.is_empty()), None);
+        }
+        let left = Self::new(self.start, self.end.min(other.start));
+        let right = Self::new(self.start.max(other.end), self.end);
+        (
+            Some(left).filter(|it| !it.is_empty()),
+            Some(right).filter(|it| !it.is_empty()),
+        )
+    }
+
+    /// The values below and from `point`, each `None` if empty.
+    pub fn split_at(&self, point: T) -> (Option<Self>, Option<Self>) {
+        let point = point.clamp(self.start, self.end.max(self.start));
+        (
+            Some(Self::new(self.start, point)).filter(|it| !it.is_empty()),
+            Some(Self::new(point, self.end)).filter(|it| !it.is_empty()),
+        )
+    }
+
+    /// Split at every one of the sorted `boundaries` inside this interval, into non-empty pieces in order.
+    pub fn split_at_all(&self, boundaries: impl IntoIterator<Item = T>) -> Vec<Self> {
+        let mut pieces = Vec::new();
+        let mut rest = Some(*self).filter(|it| !it.is_empty());
+        for boundary in boundaries {
+            let Some(current) = rest else { break };
+            let (below, from) = current.split_at(boundary);
+            pieces.extend(below);
+            rest = from;
+        }
+        pieces.extend(rest);
+        pieces
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T>> Interval<T> {
+    /// Number of values in the interval.
+    pub fn len(&self) -> T {
+        self.end - self.start.min(self.end)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> Interval<T> {
+    /// The interval moved by `offset`.
+    #[must_use]
+    pub fn shift(&self, offset: T) -> Self {
+        Self {
+            start: self.start + offset,
+            end: self.end + offset,
+        }
+    }
+}
+
+impl<T> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+/// A set of values stored as sorted, disjoint intervals. Overlapping and adjacent intervals are merged, so every set has
+/// exactly one representation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { intervals: Vec::new() }
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The intervals of the set, sorted, disjoint and with gaps between them.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The smallest value of the set.
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|it| it.start)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|it| it.end <= value);
+        self.intervals.get(index).is_some_and(|it| it.contains(value))
+    }
+
+    /// Add all values of `interval`, merging it with the intervals it overlaps or touches.
+    pub fn insert(&mut self, mut interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let first = self.intervals.partition_point(|it| it.end < interval.start);
+        let last = self.intervals.partition_point(|it| it.start <= interval.end);
+        if first < last {
+            interval.start = interval.start.min(self.intervals[first].start);
+            interval.end = interval.end.max(self.intervals[last - 1].end);
+        }
+        self.intervals.splice(first..last, [interval]);
+    }
+
+    /// Remove all values of `interval`, splitting the interval containing it if necessary.
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let (first, last) = self.overlapping_indices(interval);
+        if first == last {
+            return;
+        }
+        let (left, _) = self.intervals[first].difference(&interval);
+        let (_, right) = self.intervals[last - 1].difference(&interval);
+        self.intervals.splice(first..last, left.into_iter().chain(right));
+    }
+
+    /// The parts of the set inside `interval`, in order.
+    pub fn overlapping(&self, interval: Interval<T>) -> impl Iterator<Item = Interval<T>> + '_ {
+        let (first, last) = match interval.is_empty() {
+            true => (0, 0),
+            false => self.overlapping_indices(interval),
+        };
+        self.intervals[first..last]
+            .iter()
+            .filter_map(move |it| it.intersection(&interval))
+    }
+
+    /// The values in either set.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for &interval in other {
+            union.insert(interval);
+        }
+        union
+    }
+
+    /// The values in both sets.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        // Pieces of disjoint intervals with gaps between them keep these gaps, so they need no merging.
+        Self {
+            intervals: other.iter().flat_map(|&interval| self.overlapping(interval)).collect(),
+        }
+    }
+
+    /// The values in `self` but not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for &interval in other {
+            difference.remove(interval);
+        }
+        difference
+    }
+
+    /// Range of the indices of the intervals overlapping the non-empty `interval`.
+    fn overlapping_indices(&self, interval: Interval<T>) -> (usize, usize) {
+        let first = self.intervals.partition_point(|it| it.end <= interval.start);
+        let last = self.intervals.partition_point(|it| it.start < interval.end);
+        (first, last.max(first))
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Sum> IntervalSet<T> {
+    /// Number of values in the set.
+    pub fn coverage(&self) -> T {
+        self.iter().map(Interval::len).sum()
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+impl<T: Copy + Ord> Extend<Interval<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Interval<T>>>(&mut self, iter: I) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a IntervalSet<T> {
+    type Item = &'a Interval<T>;
+    type IntoIter = std::slice::Iter<'a, Interval<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.intervals.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::{Interval, IntervalSet};
+    use crate::generate::Rng;
+
+    fn set(ranges: &[(i64, i64)]) -> IntervalSet<i64> {
+        ranges.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    fn values(set: &IntervalSet<i64>) -> BTreeSet<i64> {
+        set.iter().flat_map(Interval::range).collect()
+    }
+
+    #[test]
+    fn test_interval_operations() {
+        let interval = Interval::new(2, 8);
+        assert_eq!(interval.len(), 6);
+        assert!(interval.contains(2) && !interval.contains(8));
+        assert_eq!(interval.intersection(&Interval::new(5, 10)), Some(Interval::new(5, 8)));
+        assert_eq!(interval.intersection(&Interval::new(8, 10)), None);
+        assert!(!interval.overlaps(&Interval::new(8, 10)));
+        assert_eq!(interval.union(&Interval::new(8, 10)), Some(Interval::new(2, 10)));
+        assert_eq!(interval.union(&Interval::new(9, 10)), None);
+        assert_eq!(
+            interval.difference(&Interval::new(4, 6)),
+            (Some(Interval::new(2, 4)), Some(Interval::new(6, 8)))
+        );
+        assert_eq!(interval.difference(&Interval::new(0, 10)), (None, None));
+        assert_eq!(interval.split_at(2), (None, Some(interval)));
+        assert_eq!(
+            interval.split_at_all([0, 3, 5, 5, 8, 12]),
+            [Interval::new(2, 3), Interval::new(3, 5), Interval::new(5, 8)]
+        );
+        assert_eq!(interval.shift(-2), Interval::from(0..6));
+    }
+
+    #[test]
+    fn test_set_merges_overlapping_and_adjacent() {
+        let set = set(&[(5, 7), (0, 2), (2, 3), (6, 9), (12, 12), (11, 12)]);
+        assert_eq!(
+            set.intervals(),
+            [Interval::new(0, 3), Interval::new(5, 9), Interval::new(11, 12)]
+        );
+        assert_eq!(set.coverage(), 8);
+        assert_eq!(set.min(), Some(0));
+        assert!(set.contains(8) && !set.contains(9));
+    }
+
+    #[test]
+    fn test_set_operations_match_sets_of_values() {
+        let mut rng = Rng::new(22);
+        let random_set = |rng: &mut Rng| {
+            let ranges = (0..rng.range(0..=5))
+                .map(|_| {
+                    let start = rng.range(0..=40).cast_signed();
+                    (start, start + rng.range(0..=10).cast_signed())
+                })
+                .collect::<Vec<_>>();
+            set(&ranges)
+        };
+        for _ in 0..500 {
+            let (a, b) = (random_set(&mut rng), random_set(&mut rng));
+            let (values_a, values_b) = (values(&a), values(&b));
+            let union = a.union(&b);
+            let intersection = a.intersection(&b);
+            let difference = a.difference(&b);
+            assert_eq!(values(&union), &values_a | &values_b, "{a:?} | {b:?}");
+            assert_eq!(values(&intersection), &values_a & &values_b, "{a:?} & {b:?}");
+            assert_eq!(values(&difference), &values_a - &values_b, "{a:?} - {b:?}");
+            for result in [union, intersection, difference] {
+                // Merging keeps a single representation of each set.
+                assert_eq!(result, result.iter().copied().collect());
+                assert_eq!(usize::try_from(result.coverage()).ok(), Some(values(&result).len()));
+            }
+        }
+    }
+}
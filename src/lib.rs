@@ -1 +1,17 @@
+pub mod answer;
+pub mod answers;
+pub mod baseline;
 pub mod days;
+pub mod generate;
+pub mod geometry;
+pub mod grid;
+pub mod inputs;
+pub mod interval;
+pub mod math;
+pub mod memory;
+pub mod parse;
+pub mod readme;
+pub mod registry;
+pub mod scaffold;
+pub mod units;
+pub mod visualize;
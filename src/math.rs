@@ -0,0 +1,247 @@
+use std::ops::{Div, Mul, RangeInclusive, Rem};
+
+/// Unsigned integer types supported by `gcd` and `lcm`.
+pub trait Unsigned: Copy + Eq + Ord + Div<Output = Self> + Mul<Output = Self> + Rem<Output = Self> {
+    const ZERO: Self;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(impl Unsigned for $t {
+            const ZERO: Self = 0;
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+        })*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+
+/// Greatest common divisor, using the Euclidean algorithm. `gcd(a, 0)` is `a`.
+pub fn gcd<T: Unsigned>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple. `lcm(a, 0)` is `0`.
+///
+/// # Panics
+///
+/// If the result overflows `T`.
+pub fn lcm<T: Unsigned>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("least common multiple to fit the type")
+}
+
+/// Least common multiple, or `None` if it overflows `T`.
+pub fn checked_lcm<T: Unsigned>(a: T, b: T) -> Option<T> {
+    match a == T::ZERO || b == T::ZERO {
+        true => Some(T::ZERO),
+        false => a.checked_mul(b / gcd(a, b)),
+    }
+}
+
+/// Extended Euclidean algorithm: `(gcd, x, y)` with `gcd = a * x + b * y`, where `gcd` is not negative.
+pub const fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut remainder, mut next_remainder) = (a, b);
+    let (mut coefficient_a, mut next_coefficient_a) = (1, 0);
+    let (mut coefficient_b, mut next_coefficient_b) = (0, 1);
+    while next_remainder != 0 {
+        let quotient = remainder / next_remainder;
+        (remainder, next_remainder) = (next_remainder, remainder - quotient * next_remainder);
+        (coefficient_a, next_coefficient_a) = (next_coefficient_a, coefficient_a - quotient * next_coefficient_a);
+        (coefficient_b, next_coefficient_b) = (next_coefficient_b, coefficient_b - quotient * next_coefficient_b);
+    }
+    match remainder < 0 {
+        true => (-remainder, -coefficient_a, -coefficient_b),
+        false => (remainder, coefficient_a, coefficient_b),
+    }
+}
+
+/// The numbers `n` with `n % modulus == residue`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Congruence {
+    pub residue: u64,
+    pub modulus: u64,
+}
+
+impl Congruence {
+    /// # Panics
+    ///
+    /// If `modulus` is zero.
+    pub const fn new(residue: u64, modulus: u64) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+        Self {
+            residue: residue % modulus,
+            modulus,
+        }
+    }
+}
+
+/// Combine all `congruences` into one, using the Chinese Remainder Theorem generalized to moduli that are not coprime.
+///
+/// The result holds for exactly the numbers satisfying all congruences, its modulus being the `lcm` of theirs.
+/// `None` if no number satisfies all congruences, or if the combined modulus overflows `u64`.
+/// The empty set of congruences is satisfied by every number, `n % 1 == 0`.
+pub fn chinese_remainder(congruences: impl IntoIterator<Item = Congruence>) -> Option<Congruence> {
+    congruences
+        .into_iter()
+        .try_fold(Congruence::new(0, 1), |combined, next| {
+            let (divisor, inverse, _) = extended_gcd(i128::from(combined.modulus), i128::from(next.modulus));
+            let difference = i128::from(next.residue) - i128::from(combined.residue);
+            if difference % divisor != 0 {
+                return None;
+            }
+            let modulus = checked_lcm(combined.modulus, next.modulus)?;
+            // combined.residue + combined.modulus * steps satisfies `next` for
+            // steps = difference / divisor * inverse (mod next.modulus / divisor).
+            let step_modulus = i128::from(next.modulus) / divisor;
+            let reduce = |n: i128| n.rem_euclid(step_modulus).unsigned_abs();
+            let steps = reduce(difference / divisor) * reduce(inverse) % step_modulus.unsigned_abs();
+            let residue = (u128::from(combined.residue) + u128::from(combined.modulus) * steps) % u128::from(modulus);
+            Some(Congruence {
+                residue: u64::try_from(residue).expect("residue to be below the modulus"),
+                modulus,
+            })
+        })
+}
+
+/// The largest integer whose square is at most `n`, computed without rounding errors.
+pub const fn isqrt(n: u128) -> u128 {
+    n.isqrt()
+}
+
+/// The integers `x` with `a * x² + b * x + c < 0`, for a positive `a`.
+///
+/// `None` if there are none, i.e. if the parabola does not dip below zero between two integers, or if the computation
+/// overflows `i128`, which takes coefficients near the limits of `i64`.
+///
+/// # Panics
+///
+/// If `a` is not positive.
+pub fn quadratic_below_zero(a: i64, b: i64, c: i64) -> Option<RangeInclusive<i64>> {
+    assert!(a > 0, "the parabola must open upwards");
+    let (a, b, c) = (i128::from(a), i128::from(b), i128::from(c));
+    let f = |x: i128| a.checked_mul(x)?.checked_add(b)?.checked_mul(x)?.checked_add(c);
+
+    let discriminant = b.checked_mul(b)?.checked_sub(a.checked_mul(c)?.checked_mul(4)?)?;
+    let discriminant = u128::try_from(discriminant).ok().filter(|&d| d > 0)?;
+    let sqrt = i128::try_from(isqrt(discriminant)).expect("square root of i128 to fit i128");
+    // The real roots are (-b ± √discriminant) / 2a. Start from the truncated ones and step to the first and last
+    // integer below zero, which are at most a step or two away.
+    let mut low = (-b - sqrt).div_euclid(2 * a);
+    while f(low)? >= 0 && low <= -b / (2 * a) {
+        low += 1;
+    }
+    while f(low - 1)? < 0 {
+        low -= 1;
+    }
+    let mut high = (-b + sqrt).div_euclid(2 * a) + 1;
+    while f(high)? >= 0 && high >= low {
+        high -= 1;
+    }
+    while f(high + 1)? < 0 {
+        high += 1;
+    }
+    match f(low)? < 0 && low <= high {
+        true => Some(i64::try_from(low).ok()?..=i64::try_from(high).ok()?),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{checked_lcm, chinese_remainder, extended_gcd, gcd, isqrt, lcm, quadratic_below_zero, Congruence};
+
+    #[test]
+    fn test_gcd_and_lcm() {
+        assert_eq!(gcd(48u64, 18), 6);
+        assert_eq!(gcd(18u8, 48), 6);
+        assert_eq!(gcd(0u32, 7), 7);
+        assert_eq!(gcd(7usize, 0), 7);
+        assert_eq!(lcm(21u64, 6), 42);
+        assert_eq!(lcm(6u16, 21), 42);
+        assert_eq!(lcm(0u128, 21), 0);
+        assert_eq!(checked_lcm(200u8, 3), None);
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (46, 240), (0, 5), (5, 0), (-12, 18), (17, 5), (1 << 62, 3)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, i128::try_from(gcd(a.unsigned_abs(), b.unsigned_abs())).expect("gcd"));
+            assert_eq!(a * x + b * y, g, "a {a}, b {b}");
+        }
+    }
+
+    #[test]
+    fn test_chinese_remainder() {
+        let solve = |congruences: &[(u64, u64)]| {
+            chinese_remainder(
+                congruences
+                    .iter()
+                    .map(|&(residue, modulus)| Congruence::new(residue, modulus)),
+            )
+        };
+        assert_eq!(solve(&[(2, 3), (3, 5), (2, 7)]), Some(Congruence::new(23, 105)));
+        // Moduli that are not coprime.
+        assert_eq!(solve(&[(3, 4), (5, 6)]), Some(Congruence::new(11, 12)));
+        assert_eq!(solve(&[(3, 4), (4, 6)]), None);
+        assert_eq!(solve(&[]), Some(Congruence::new(0, 1)));
+        // The same cycle lengths as day 8 ghosts reaching their targets at the end of each cycle.
+        assert_eq!(
+            solve(&[(0, 20_777), (0, 16_043)]),
+            Some(Congruence::new(0, lcm(20_777, 16_043)))
+        );
+        // Two large primes, whose product almost fills u64.
+        let (p, q) = ((1 << 32) + 15, (1 << 32) - 17);
+        let large = solve(&[(1, p), (2, q)]).expect("solution");
+        assert_eq!((large.residue % p, large.residue % q), (1, 2));
+        assert_eq!(solve(&[(0, u64::MAX), (0, u64::MAX - 1)]), None);
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        let large = u128::from(u64::MAX);
+        assert_eq!(isqrt(large * large), large);
+        assert_eq!(isqrt(large * large - 1), large - 1);
+    }
+
+    #[test]
+    fn test_quadratic_below_zero() {
+        // x * (7 - x) > 9, the first race of the day 6 example.
+        assert_eq!(quadratic_below_zero(1, -7, 9), Some(2..=5));
+        // x * (30 - x) > 200: the roots 10 and 20 are integers themselves, but not below zero.
+        assert_eq!(quadratic_below_zero(1, -30, 200), Some(11..=19));
+        assert_eq!(quadratic_below_zero(1, 0, 0), None);
+        assert_eq!(quadratic_below_zero(1, 0, -1), Some(0..=0));
+        assert_eq!(quadratic_below_zero(16, -16, 3), None);
+        assert_eq!(quadratic_below_zero(2, 3, -20), Some(-3..=2));
+        // x² < 2^63, whose root is just below 3037000500.
+        assert_eq!(
+            quadratic_below_zero(1, 0, i64::MIN),
+            Some(-3_037_000_499..=3_037_000_499)
+        );
+        // 4 * a * c overflows i128.
+        assert_eq!(quadratic_below_zero(i64::MAX, 0, i64::MAX), None);
+        assert_eq!(quadratic_below_zero(i64::MAX, i64::MIN, i64::MIN), None);
+        for a in 1..5 {
+            for b in -20..20 {
+                for c in -20..20 {
+                    let f = |x: i64| a * x * x + b * x + c;
+                    let expected = (-50..50).filter(|&x| f(x) < 0).collect::<Vec<_>>();
+                    let range = quadratic_below_zero(a, b, c).map(Iterator::collect::<Vec<_>>);
+                    assert_eq!(range.unwrap_or_default(), expected, "a {a}, b {b}, c {c}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Heap usage of a piece of code, as counted by [`CountingAllocator`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Maximum number of bytes allocated at once, on top of what was allocated before.
+    pub peak_bytes: usize,
+    /// Number of allocations, including reallocations.
+    pub allocations: usize,
+    /// Sum of the sizes of all allocations.
+    pub allocated_bytes: usize,
+}
+
+/// Counters of the current thread. Const-initialized and without destructor, so that accessing them from within the
+/// allocator never allocates itself.
+#[derive(Debug)]
+struct Counters {
+    /// Bytes allocated and not yet freed by this thread. May become negative for memory freed on another thread.
+    current: Cell<isize>,
+    peak: Cell<isize>,
+    allocations: Cell<usize>,
+    allocated_bytes: Cell<usize>,
+}
+
+thread_local! {
+    static COUNTERS: Counters = const {
+        Counters {
+            current: Cell::new(0),
+            peak: Cell::new(0),
+            allocations: Cell::new(0),
+            allocated_bytes: Cell::new(0),
+        }
+    };
+}
+
+impl Counters {
+    fn alloc(&self, size: usize) {
+        self.allocations.set(self.allocations.get() + 1);
+        self.allocated_bytes.set(self.allocated_bytes.get() + size);
+        self.current
+            .set(self.current.get() + isize::try_from(size).unwrap_or(isize::MAX));
+        self.peak.set(isize::max(self.peak.get(), self.current.get()));
+    }
+
+    fn dealloc(&self, size: usize) {
+        self.current
+            .set(self.current.get() - isize::try_from(size).unwrap_or(isize::MAX));
+    }
+}
+
+/// Global allocator counting the allocations of each thread, forwarding to the system allocator.
+///
+/// Register it in a binary using `#[global_allocator]`, then use [`measure`] to get the heap usage of a closure.
+/// Counting per thread keeps measurements of solutions running in parallel apart.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CountingAllocator;
+
+// SAFETY: All allocation work is delegated to `System`. Counting only touches thread-local cells, never allocating.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = COUNTERS.try_with(|c| c.alloc(layout.size()));
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::alloc`.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let _ = COUNTERS.try_with(|c| c.alloc(layout.size()));
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::alloc_zeroed`.
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = COUNTERS.try_with(|c| c.dealloc(layout.size()));
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::dealloc`.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = COUNTERS.try_with(|c| {
+            c.dealloc(layout.size());
+            c.alloc(new_size);
+        });
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::realloc`.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// Run `f`, counting the heap usage of the current thread while doing so.
+///
+/// Only meaningful if [`CountingAllocator`] is the global allocator. Otherwise, all counts are zero.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, AllocStats) {
+    let (start_current, start_allocations, start_allocated_bytes) = COUNTERS.with(|c| {
+        c.peak.set(c.current.get());
+        (c.current.get(), c.allocations.get(), c.allocated_bytes.get())
+    });
+    let result = f();
+    let stats = COUNTERS.with(|c| AllocStats {
+        peak_bytes: usize::try_from(c.peak.get() - start_current).unwrap_or_default(),
+        allocations: c.allocations.get() - start_allocations,
+        allocated_bytes: c.allocated_bytes.get() - start_allocated_bytes,
+    });
+    (result, stats)
+}
+
+#[cfg(test)]
+mod test {
+    use super::measure;
+
+    #[test]
+    fn test_measure_without_counting_allocator() {
+        let (v, stats) = measure(|| vec![1u8; 16]);
+        assert_eq!(v.len(), 16);
+        assert_eq!(stats.allocations, 0);
+        assert_eq!(stats.peak_bytes, 0);
+    }
+}
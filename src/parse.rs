@@ -0,0 +1,396 @@
+use std::{fmt::Display, marker::PhantomData, str::FromStr};
+
+/// Failure to parse a puzzle input, pointing at the offending text.
+///
+/// Displayed like a compiler diagnostic, with a caret under the offending text:
+///
+/// ```text
+/// expected a color (red, green or blue), found `purple`
+///  --> line 3, column 13
+///   |
+/// 3 | Game 3: 8 purple, 6 blue
+///   |           ^^^^^^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// The offending text. Empty if something is missing, e.g. at the end of a line.
+    pub found: String,
+    /// What the parser expected instead, e.g. `a number`.
+    pub expected: String,
+    /// The whole line containing the offending text.
+    pub source_line: String,
+}
+
+impl ParseError {
+    /// An error for `found`, which must be a subslice of `input`, the complete puzzle input.
+    /// Pass an empty slice (e.g. `&line[line.len()..]`) to report something missing at that position.
+    ///
+    /// If `found` is not part of `input`, the error points at the start of the input.
+    pub fn new(input: &str, found: &str, expected: impl Into<String>) -> Self {
+        Self::at_bytes(input, found.as_bytes(), expected)
+    }
+
+    /// Like [`ParseError::new`], for `found` being a subslice of the bytes of `input`.
+    pub fn at_bytes(input: &str, found: &[u8], expected: impl Into<String>) -> Self {
+        let mut offset = offset_in(input.as_bytes(), found).unwrap_or_default();
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..].find('\n').map_or(input.len(), |i| offset + i);
+        Self {
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            found: String::from_utf8_lossy(found)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_owned(),
+            expected: expected.into(),
+            source_line: input[line_start..line_end].trim_end_matches('\r').to_owned(),
+        }
+    }
+
+    /// An error for input ending before `expected` was found.
+    pub fn end_of_input(input: &str, expected: impl Into<String>) -> Self {
+        Self::new(input, &input[input.trim_end().len()..input.trim_end().len()], expected)
+    }
+}
+
+/// Byte offset of `part` in `whole`, if `part` is a subslice of it.
+fn offset_in(whole: &[u8], part: &[u8]) -> Option<usize> {
+    let whole_range = whole.as_ptr_range();
+    let part_range = part.as_ptr_range();
+    match whole_range.start <= part_range.start && part_range.end <= whole_range.end {
+        true => Some(part_range.start as usize - whole_range.start as usize),
+        false => None,
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.found.is_empty() {
+            true => writeln!(f, "expected {}, found nothing", self.expected)?,
+            false => writeln!(f, "expected {}, found `{}`", self.expected, self.found)?,
+        }
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{gutter}--> line {}, column {}", self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.found.chars().count().max(1))
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse `s`, a subslice of `input`, as a number of type `T`.
+///
+/// # Errors
+///
+/// Fails, pointing at `s`, if it is not a valid `T`.
+pub fn number<T: FromStr>(input: &str, s: &str) -> Result<T, ParseError> {
+    s.parse::<T>().map_err(|_err| ParseError::new(input, s, "a number"))
+}
+
+/// Integer types read by the byte-level scanners, e.g. [`integers`].
+pub trait Integer: Copy {
+    const ZERO: Self;
+    /// Whether a leading `-` is part of the number.
+    const SIGNED: bool;
+    /// Number of digits that always fit, e.g. 2 for `u8`.
+    const SAFE_DIGITS: usize;
+
+    /// Append `digit` to the decimal digits of `self`, subtracting it for `negative` numbers so that the minimum value
+    /// can be read. `None` on overflow.
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+
+    /// [`Integer::push_digit`] without the overflow check, for the first [`Integer::SAFE_DIGITS`] digits.
+    #[must_use]
+    fn push_safe_digit(self, digit: u8, negative: bool) -> Self;
+}
+
+macro_rules! impl_unsigned_integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            const SIGNED: bool = false;
+            const SAFE_DIGITS: usize = Self::MAX.ilog10() as usize;
+
+            #[inline]
+            fn push_digit(self, digit: u8, _negative: bool) -> Option<Self> {
+                self.checked_mul(10)?.checked_add(Self::from(digit))
+            }
+
+            #[inline]
+            fn push_safe_digit(self, digit: u8, _negative: bool) -> Self {
+                self.wrapping_mul(10).wrapping_add(Self::from(digit))
+            }
+        })*
+    };
+}
+
+macro_rules! impl_signed_integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            const SIGNED: bool = true;
+            const SAFE_DIGITS: usize = Self::MAX.ilog10() as usize;
+
+            #[inline]
+            fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                let digit = Self::from(digit.cast_signed());
+                match negative {
+                    true => self.checked_mul(10)?.checked_sub(digit),
+                    false => self.checked_mul(10)?.checked_add(digit),
+                }
+            }
+
+            #[inline]
+            fn push_safe_digit(self, digit: u8, negative: bool) -> Self {
+                let digit = Self::from(digit.cast_signed());
+                match negative {
+                    true => self.wrapping_mul(10).wrapping_sub(digit),
+                    false => self.wrapping_mul(10).wrapping_add(digit),
+                }
+            }
+        })*
+    };
+}
+
+impl_unsigned_integer!(u8, u16, u32, u64, u128, usize);
+impl_signed_integer!(i8, i16, i32, i64, i128, isize);
+
+/// Read the integer at the start of `bytes`: decimal digits, preceded by a `-` for signed `T`.
+///
+/// Returns it with the number of bytes read, or `None` if `bytes` does not start with an integer or it does not fit
+/// into `T`.
+#[inline]
+pub fn scan_integer<T: Integer>(bytes: &[u8]) -> Option<(T, usize)> {
+    let negative = T::SIGNED && bytes.first() == Some(&b'-');
+    let start = usize::from(negative);
+    let mut value = T::ZERO;
+    for (len, &byte) in bytes[start..].iter().enumerate() {
+        let digit = byte.wrapping_sub(b'0');
+        if digit >= 10 {
+            return (len > 0).then_some((value, start + len));
+        }
+        value = match len < T::SAFE_DIGITS {
+            true => value.push_safe_digit(digit, negative),
+            false => value.push_digit(digit, negative)?,
+        };
+    }
+    (bytes.len() > start).then_some((value, bytes.len()))
+}
+
+/// Parse all of `bytes`, a subslice of `input`, as an integer of type `T`. The byte-level counterpart of [`number`].
+///
+/// # Errors
+///
+/// Fails, pointing at `bytes`, if it is not a valid `T`.
+pub fn integer<T: Integer>(input: &str, bytes: &[u8]) -> Result<T, ParseError> {
+    match scan_integer(bytes) {
+        Some((value, len)) if len == bytes.len() => Ok(value),
+        _ => Err(ParseError::at_bytes(input, bytes, "a number")),
+    }
+}
+
+/// All integers of `line`, a subslice of `input`, separated by any amount of ASCII whitespace, e.g. ` 41 48  83`.
+///
+/// Yields an error, pointing at the offending text, for everything between the whitespace that is not a `T`.
+pub const fn integers<'a, T: Integer>(input: &'a str, line: &'a [u8]) -> Integers<'a, T> {
+    Integers {
+        input,
+        rest: line,
+        delimiter: None,
+        done: false,
+        integer: PhantomData,
+    }
+}
+
+/// The integers of `line`, a subslice of `input`, separated by exactly one `delimiter`, e.g. `1,1,3` for `b','`.
+///
+/// Unlike [`integers`], a missing number is an error, e.g. in `1,,3`, at the start or end, or for an empty `line`.
+pub const fn delimited<'a, T: Integer>(input: &'a str, line: &'a [u8], delimiter: u8) -> Integers<'a, T> {
+    Integers {
+        input,
+        rest: line,
+        delimiter: Some(delimiter),
+        done: false,
+        integer: PhantomData,
+    }
+}
+
+/// Iterator over the integers of a line, see [`integers`] and [`delimited`]. Reads the line in a single pass, without
+/// allocating.
+#[derive(Debug, Clone)]
+pub struct Integers<'a, T> {
+    input: &'a str,
+    rest: &'a [u8],
+    /// Separator of the integers, any amount of whitespace if `None`.
+    delimiter: Option<u8>,
+    /// Whether the last integer after a delimiter has been read.
+    done: bool,
+    integer: PhantomData<T>,
+}
+
+impl<'a, T> Integers<'a, T> {
+    /// The part of the line not read yet.
+    pub const fn remainder(&self) -> &'a [u8] {
+        self.rest
+    }
+
+    /// Skip the text up to the next separator, which is not a number, and report it.
+    #[cold]
+    fn skip_invalid(&mut self) -> ParseError {
+        let len = self
+            .rest
+            .iter()
+            .position(|&byte| self.is_separator(byte))
+            .unwrap_or(self.rest.len());
+        let (found, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        ParseError::at_bytes(self.input, found, "a number")
+    }
+
+    const fn is_separator(&self, byte: u8) -> bool {
+        match self.delimiter {
+            Some(delimiter) => byte == delimiter,
+            None => byte.is_ascii_whitespace(),
+        }
+    }
+}
+
+impl<T: Integer> Iterator for Integers<'_, T> {
+    type Item = Result<T, ParseError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.delimiter {
+            Some(_) if self.done => return None,
+            Some(_) => {}
+            None => {
+                self.rest = self.rest.trim_ascii_start();
+                if self.rest.is_empty() {
+                    return None;
+                }
+            }
+        }
+        let result = match scan_integer(self.rest) {
+            Some((value, len)) if self.rest.get(len).is_none_or(|&byte| self.is_separator(byte)) => {
+                self.rest = &self.rest[len..];
+                Ok(value)
+            }
+            _ => Err(self.skip_invalid()),
+        };
+        if self.delimiter.is_some() {
+            // The integer is followed by the delimiter or the end of the line.
+            match self.rest.split_first() {
+                Some((_, rest)) => self.rest = rest,
+                None => self.done = true,
+            }
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{delimited, integer, integers, number, scan_integer, ParseError};
+
+    #[test]
+    fn test_error_position_and_rendering() {
+        let input = "Game 1: 3 blue\nGame 2: 8 purple, 6 blue\n";
+        let found = &input[25..31];
+        let err = ParseError::new(input, found, "a color");
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 11, "purple"));
+        assert_eq!(
+            err.to_string(),
+            "expected a color, found `purple`\n --> line 2, column 11\n  |\n2 | Game 2: 8 purple, 6 blue\n  |           ^^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_missing_text_is_reported_at_its_position() {
+        let input = "seeds: 1 2\n\nsoil";
+        let line = &input[13..];
+        let err = ParseError::new(input, &line[line.len()..], "a colon");
+        assert_eq!((err.line, err.column, err.found.as_str()), (3, 5, ""));
+        assert!(err.to_string().ends_with("3 | soil\n  |     ^"));
+
+        let err = ParseError::end_of_input(input, "a block");
+        assert_eq!((err.line, err.column), (3, 5));
+    }
+
+    #[test]
+    fn test_number() {
+        let input = "12 x4";
+        assert_eq!(number::<u8>(input, &input[..2]), Ok(12));
+        let err = number::<u8>(input, &input[3..]).expect_err("not a number");
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 4, "x4"));
+    }
+
+    #[test]
+    fn test_scan_integer() {
+        assert_eq!(scan_integer::<u8>(b"255,"), Some((255, 3)));
+        assert_eq!(scan_integer::<u8>(b"256"), None);
+        assert_eq!(scan_integer::<u8>(b"-1"), None);
+        assert_eq!(scan_integer::<i8>(b"-128 "), Some((-128, 4)));
+        assert_eq!(scan_integer::<i8>(b"128"), None);
+        assert_eq!(scan_integer::<i64>(b"-"), None);
+        assert_eq!(scan_integer::<u64>(b"007x"), Some((7, 3)));
+        assert_eq!(scan_integer::<u64>(b""), None);
+    }
+
+    #[test]
+    fn test_integer() {
+        let input = "12 -4 4x";
+        assert_eq!(integer::<u8>(input, &input.as_bytes()[..2]), Ok(12));
+        assert_eq!(integer::<i32>(input, &input.as_bytes()[3..5]), Ok(-4));
+        let err = integer::<u32>(input, &input.as_bytes()[3..5]).expect_err("negative");
+        assert_eq!((err.column, err.found.as_str()), (4, "-4"));
+        let err = integer::<u32>(input, &input.as_bytes()[6..]).expect_err("trailing letter");
+        assert_eq!((err.column, err.found.as_str()), (7, "4x"));
+    }
+
+    #[test]
+    fn test_integers() {
+        let line = " 1  -2\t42   99  ";
+        let values = integers::<i64>(line, line.as_bytes()).collect::<Result<Vec<_>, _>>();
+        assert_eq!(values, Ok(vec![1, -2, 42, 99]));
+        assert_eq!(integers::<u8>("", b"").count(), 0);
+
+        let line = "1 2x 300 4";
+        let results = integers::<u8>(line, line.as_bytes()).collect::<Vec<_>>();
+        let errors = results.iter().filter_map(|it| it.as_ref().err());
+        let errors = errors.map(|err| (err.column, err.found.as_str())).collect::<Vec<_>>();
+        assert_eq!(errors, [(3, "2x"), (6, "300")]);
+        assert_eq!(results.len(), 4);
+
+        let mut numbers = integers::<u8>(line, line.as_bytes());
+        numbers.next();
+        assert_eq!(numbers.remainder(), b" 2x 300 4");
+    }
+
+    #[test]
+    fn test_delimited() {
+        let line = "1,1,3";
+        let values = delimited::<u8>(line, line.as_bytes(), b',').collect::<Result<Vec<_>, _>>();
+        assert_eq!(values, Ok(vec![1, 1, 3]));
+
+        for (line, column) in [("1,,3", 3), ("1,2,", 5), (",1", 1), ("", 1), ("1, 2", 3)] {
+            let err = delimited::<u8>(line, line.as_bytes(), b',')
+                .collect::<Result<Vec<_>, _>>()
+                .expect_err("missing number");
+            assert_eq!(err.column, column, "{line}");
+        }
+    }
+}
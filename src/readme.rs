@@ -0,0 +1,233 @@
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+use crate::units::{format_duration, parse_duration};
+
+/// Marks the start of the generated benchmark table in `README.md`.
+pub const BEGIN_MARKER: &str = "<!-- benchmarks:begin -->";
+/// Marks the end of the generated benchmark table in `README.md`.
+pub const END_MARKER: &str = "<!-- benchmarks:end -->";
+
+/// Timings of one solution run against one of its inputs.
+#[derive(Debug, Clone)]
+pub struct BenchRow {
+    pub day: u8,
+    /// Name of the solution function, e.g. `part2_expand_9`.
+    pub name: &'static str,
+    /// Name of the input, e.g. `real` or `part1_test2`.
+    pub input: String,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl BenchRow {
+    fn key(&self) -> String {
+        key(self.day, self.name, &self.input)
+    }
+}
+
+fn key(day: u8, name: &str, input: &str) -> String {
+    format!("{day}/{name}/{input}")
+}
+
+/// Everything printed above the table.
+#[derive(Debug, Clone)]
+pub struct BenchInfo {
+    /// Description of the machine the benchmarks ran on, e.g. `AMD Ryzen 9 7950X, 32 threads, linux x86_64`.
+    pub machine: String,
+    /// `git describe` of the benchmarked build.
+    pub version: String,
+    /// Number of runs per solution.
+    pub runs: u32,
+}
+
+/// Describe the machine this runs on: CPU model (if known), available parallelism, OS and architecture.
+pub fn machine() -> String {
+    let cpu = std::fs::read_to_string("/proc/cpuinfo").ok().and_then(|info| {
+        info.lines()
+            .find_map(|line| line.strip_prefix("model name"))
+            .and_then(|line| line.split_once(':'))
+            .map(|(_, model)| model.trim().to_owned())
+    });
+    let threads = std::thread::available_parallelism().map_or(1, usize::from);
+    let mut machine = cpu.map(|cpu| format!("{cpu}, ")).unwrap_or_default();
+    let _ = write!(
+        machine,
+        "{threads} threads, {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    machine
+}
+
+/// The content between the benchmark markers of `readme`, if both are present.
+pub fn region(readme: &str) -> Option<&str> {
+    let start = readme.find(BEGIN_MARKER)? + BEGIN_MARKER.len();
+    let end = start + readme[start..].find(END_MARKER)?;
+    Some(&readme[start..end])
+}
+
+/// Replace the content between the benchmark markers of `readme` with `content`.
+/// `None` if `readme` does not contain both markers.
+pub fn replace_region(readme: &str, content: &str) -> Option<String> {
+    let start = readme.find(BEGIN_MARKER)? + BEGIN_MARKER.len();
+    let end = start + readme[start..].find(END_MARKER)?;
+    Some(format!("{}\n{}{}", &readme[..start], content, &readme[end..]))
+}
+
+/// The start of the tables written by [`render`] for `machine`.
+fn source(machine: &str) -> String {
+    format!("Measured with `just readme` on {machine} at `")
+}
+
+/// Median times of a table previously written by [`render`], keyed by day, solution name and input.
+///
+/// Empty unless the table was measured on `machine`, so that only timings of the same source are compared.
+pub fn previous_medians(table: &str, machine: &str) -> BTreeMap<String, Duration> {
+    if !table.trim_start().starts_with(&source(machine)) {
+        return BTreeMap::new();
+    }
+    table
+        .lines()
+        .filter_map(|line| {
+            let cells = line
+                .trim()
+                .strip_prefix('|')?
+                .split('|')
+                .map(str::trim)
+                .collect::<Vec<_>>();
+            let day = cells.first()?.parse::<u8>().ok()?;
+            let name = cells.get(2)?.trim_matches('`');
+            let input = cells.get(3)?;
+            let median = parse_duration(cells.get(4)?)?;
+            Some((key(day, name, input), median))
+        })
+        .collect()
+}
+
+/// Render the benchmark table, comparing each median with the one of the `previous` table.
+pub fn render(info: &BenchInfo, rows: &[BenchRow], previous: &BTreeMap<String, Duration>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{}{}`:\nall inputs, {} runs per part and input including parsing.",
+        source(&info.machine),
+        info.version,
+        info.runs
+    );
+    let _ = writeln!(
+        out,
+        "Δ compares the median with the previous version of this table, if it was measured on the same machine.\n"
+    );
+    let _ = writeln!(out, "| day | title | part | input | median | min | max | Δ |");
+    let _ = writeln!(out, "|----:|:------|:-----|:------|-------:|----:|----:|--:|");
+    for row in rows {
+        let delta = previous
+            .get(&row.key())
+            .map_or_else(|| String::from("new"), |&previous| format_delta(previous, row.median));
+        let _ = writeln!(
+            out,
+            "| {} | {} | `{}` | {} | {} | {} | {} | {} |",
+            row.day,
+            crate::registry::day(row.day).map_or("", |day| day.title),
+            row.name,
+            row.input,
+            format_duration(row.median),
+            format_duration(row.min),
+            format_duration(row.max),
+            delta
+        );
+    }
+    out
+}
+
+/// Relative change from `previous` to `current`, e.g. `-12.5 %`.
+fn format_delta(previous: Duration, current: Duration) -> String {
+    if previous.is_zero() {
+        return String::from("-");
+    }
+    let change = (current.as_secs_f64() / previous.as_secs_f64() - 1.0) * 100.0;
+    format!("{change:+.1} %")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{previous_medians, region, render, replace_region, BenchInfo, BenchRow, BEGIN_MARKER, END_MARKER};
+    use std::{collections::BTreeMap, time::Duration};
+
+    fn info() -> BenchInfo {
+        BenchInfo {
+            machine: String::from("Test CPU, 4 threads, linux x86_64"),
+            version: String::from("abc1234"),
+            runs: 10,
+        }
+    }
+
+    fn row(day: u8, name: &'static str, input: &str, median_micros: u64) -> BenchRow {
+        BenchRow {
+            day,
+            name,
+            input: String::from(input),
+            min: Duration::from_micros(median_micros - 1),
+            median: Duration::from_micros(median_micros),
+            max: Duration::from_micros(median_micros + 1),
+        }
+    }
+
+    #[test]
+    fn test_render_reports_deltas_against_previous_table() {
+        let first = render(&info(), &[row(1, "part1", "real", 100)], &BTreeMap::new());
+        assert!(first.contains("| 1 | Trebuchet?! | `part1` | real | 100.0 μs | 99.0 μs | 101.0 μs | new |"));
+
+        let previous = previous_medians(&first, &info().machine);
+        assert_eq!(previous.get("1/part1/real"), Some(&Duration::from_micros(100)));
+        let rows = [
+            row(1, "part1", "part1_test", 2),
+            row(1, "part1", "real", 80),
+            row(1, "part2", "real", 5),
+        ];
+        let second = render(&info(), &rows, &previous);
+        assert!(second.contains("| part1_test | 2.0 μs | 1.0 μs | 3.0 μs | new |"));
+        assert!(second.contains("| real | 80.0 μs | 79.0 μs | 81.0 μs | -20.0 % |"));
+        assert!(second.contains("| `part2` | real | 5.0 μs | 4.0 μs | 6.0 μs | new |"));
+    }
+
+    #[test]
+    fn test_previous_medians_only_reads_tables_of_the_same_source() {
+        let table = "\
+Measured with `just readme` on Test CPU, 4 threads, linux x86_64 at `abc1234`:
+all inputs, 10 runs per part and input including parsing.
+
+| day | title | part | input | median | min | max | Δ |
+|----:|:------|:-----|:------|-------:|----:|----:|--:|
+| 10 | Pipe Maze | `part2` | part2_test3 | 1.2 μs | 1.1 μs | 1.5 μs | new |
+| 11 | Cosmic Expansion | `part2_expand_999_999` | real | 2.5 ms | 2.4 ms | 2.9 ms | -1.0 % |
+";
+        let previous = previous_medians(table, "Test CPU, 4 threads, linux x86_64");
+        assert_eq!(
+            previous.into_iter().collect::<Vec<_>>(),
+            [
+                (String::from("10/part2/part2_test3"), Duration::from_nanos(1_200)),
+                (
+                    String::from("11/part2_expand_999_999/real"),
+                    Duration::from_micros(2_500)
+                ),
+            ]
+        );
+        assert!(previous_medians(table, "Test CPU, 8 threads, linux x86_64").is_empty());
+        let by_hand = table.replace("Measured with `just readme` on", "Measured with `cargo bench` on");
+        assert!(previous_medians(&by_hand, "Test CPU, 4 threads, linux x86_64").is_empty());
+    }
+
+    #[test]
+    fn test_replace_region() {
+        let readme = format!("# Title\n\n{BEGIN_MARKER}\nold\n{END_MARKER}\n\nmore\n");
+        let replaced = replace_region(&readme, "new\n").expect("markers present");
+        assert_eq!(
+            replaced,
+            format!("# Title\n\n{BEGIN_MARKER}\nnew\n{END_MARKER}\n\nmore\n")
+        );
+        assert_eq!(region(&replaced), Some("\nnew\n"));
+        assert_eq!(replace_region("# Title", "new\n"), None);
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::{Duration, Instant};
+
+use crate::answer::Answer;
+use crate::inputs::InputId;
+use crate::parse::ParseError;
+use crate::visualize::Canvas;
+
+/// A day of the calendar, with all of its registered solutions.
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    pub day: u8,
+    /// Title of the puzzle, e.g. `"Trebuchet?!"`.
+    pub title: &'static str,
+    pub solutions: &'static [Solution],
+    /// Parses an input with the `parse` function of the day, discarding the result. Used to benchmark parsing alone.
+    pub parse: fn(&str) -> Result<(), ParseError>,
+    /// Parses an input and draws it annotated with what the solutions found, for days providing a `visualize` function.
+    #[allow(clippy::type_complexity)]
+    pub visualize: Option<fn(&str) -> Result<Canvas, ParseError>>,
+}
+
+impl Day {
+    /// Inputs of all solutions of this day, without duplicates, in order of first occurrence.
+    pub fn inputs(&self) -> Vec<InputId> {
+        let mut inputs = Vec::new();
+        for &id in self.solutions.iter().flat_map(|s| s.inputs) {
+            if !inputs.contains(&id) {
+                inputs.push(id);
+            }
+        }
+        inputs
+    }
+}
+
+/// One solution function of a day, e.g. `day7::part1`.
+#[derive(Debug, Clone, Copy)]
+pub struct Solution {
+    pub day: u8,
+    /// The puzzle part (1 or 2) this solution solves.
+    pub part: u8,
+    /// Name of the solution function, e.g. `part1` or `part2_expand_9`.
+    pub name: &'static str,
+    /// Inputs this solution is known to work with, examples first.
+    pub inputs: &'static [InputId],
+    /// Type returned by the solution function, e.g. `u64`.
+    pub answer_type: &'static str,
+    /// Parses the input with the `parse` function of the day and runs the solution function on the result.
+    pub solve: fn(&str) -> Result<Answer, ParseError>,
+    /// Like `solve`, but measuring the parse and the solve step separately.
+    pub solve_timed: fn(&str) -> Result<Timed, ParseError>,
+}
+
+/// Answer of one run of a solution, with the time spent in its parse and solve steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timed {
+    pub answer: Answer,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Timed {
+    pub fn total(&self) -> Duration {
+        self.parse + self.solve
+    }
+}
+
+/// Run `parse` on `input` and `solve` on its result, measuring both steps.
+///
+/// # Errors
+///
+/// Fails without solving if `parse` fails.
+pub fn timed<'a, P, R: Into<Answer>>(
+    input: &'a str,
+    parse: impl FnOnce(&'a str) -> Result<P, ParseError>,
+    solve: impl FnOnce(&P) -> R,
+) -> Result<Timed, ParseError> {
+    let start = Instant::now();
+    let parsed = tracing::debug_span!("parse").in_scope(|| parse(input))?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let answer = tracing::debug_span!("solve").in_scope(|| solve(&parsed));
+    let solve = start.elapsed();
+
+    Ok(Timed {
+        answer: answer.into(),
+        parse,
+        solve,
+    })
+}
+
+/// Type returned by a solution function.
+pub trait SolutionResult: Into<Answer> {
+    const TYPE_NAME: &'static str;
+}
+
+macro_rules! impl_solution_result {
+    ($($t:ty),*) => {
+        $(impl SolutionResult for $t {
+            const TYPE_NAME: &'static str = stringify!($t);
+        })*
+    };
+}
+
+impl_solution_result!(u32, u64, i64, Option<i64>, Option<u64>);
+
+/// Name of the type returned by the solution function `_f`.
+pub const fn answer_type<P, R: SolutionResult, F: Fn(P) -> R>(_f: &F) -> &'static str {
+    R::TYPE_NAME
+}
+
+/// Declares the day modules and registers their solutions in `DAYS`.
+///
+/// ```ignore
+/// days! {
+///     1 day1 "Trebuchet?!" {
+///         1 part1 ["part1_test", real],
+///         2 part2 ["part2_test", real],
+///     }
+/// }
+/// ```
+///
+/// Every day module provides a fallible `parse` function, whose result is passed by reference to each of its solutions.
+/// Each solution lists the inputs it is run against: the variant names of example inputs or `real`.
+/// Days marked `visualize` after their title also provide a `visualize` function, drawing the parsed input as a
+/// `Canvas`, e.g. `10 day10 "Pipe Maze" visualize { ... }`.
+macro_rules! days {
+    ($($day:literal $module:ident $title:literal $($visualize:ident)? {
+        $($part:literal $name:ident [$($input:tt),* $(,)?]),* $(,)?
+    })*) => {
+        $(pub mod $module;)*
+
+        /// All days and their solutions, ordered by day.
+        pub static DAYS: &[$crate::registry::Day] = &[$(
+            $crate::registry::Day {
+                day: $day,
+                title: $title,
+                solutions: &[$(
+                    $crate::registry::Solution {
+                        day: $day,
+                        part: $part,
+                        name: stringify!($name),
+                        inputs: &[$($crate::registry::days!(@input $day $input)),*],
+                        answer_type: $crate::registry::answer_type(&$module::$name),
+                        solve: |input| $module::parse(input).map(|parsed| $crate::answer::Answer::from($module::$name(&parsed))),
+                        solve_timed: |input| $crate::registry::timed(input, $module::parse, |parsed| $module::$name(parsed)),
+                    }
+                ),*],
+                parse: |input| $module::parse(input).map(|parsed| {
+                    std::hint::black_box(parsed);
+                }),
+                visualize: $crate::registry::days!(@visualize $module $($visualize)?),
+            }
+        ),*];
+    };
+    (@visualize $module:ident visualize) => {
+        Some(|input| $module::parse(input).map(|parsed| $module::visualize(&parsed)))
+    };
+    (@visualize $module:ident) => {
+        None
+    };
+    (@input $day:literal real) => {
+        $crate::inputs::InputId::real($day)
+    };
+    (@input $day:literal $variant:literal) => {
+        $crate::inputs::InputId::test($day, $variant)
+    };
+}
+
+pub(crate) use days;
+
+/// Iterate over the solutions of all days, ordered by day.
+pub fn solutions() -> impl Iterator<Item = &'static Solution> {
+    crate::days::DAYS.iter().flat_map(|day| day.solutions)
+}
+
+/// The registered day `day`, if any.
+pub fn day(day: u8) -> Option<&'static Day> {
+    crate::days::DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::solutions;
+    use crate::answers::{Answers, ANSWERS_FILE};
+    use crate::days::DAYS;
+    use crate::inputs::Inputs;
+
+    #[test]
+    fn test_days_are_ordered_and_unique() {
+        assert!(DAYS.windows(2).all(|days| days[0].day < days[1].day));
+        for day in DAYS {
+            assert!(!day.solutions.is_empty());
+            assert!(day
+                .solutions
+                .iter()
+                .all(|s| s.day == day.day && (1..=2).contains(&s.part)));
+        }
+    }
+
+    #[test]
+    fn test_registered_example_inputs_exist() {
+        let inputs = Inputs::new(concat!(env!("CARGO_MANIFEST_DIR"), "/res"));
+        for solution in solutions() {
+            for input in solution.inputs.iter().filter(|input| input.is_test()) {
+                assert!(
+                    inputs.path(*input).exists(),
+                    "{} is missing",
+                    inputs.path(*input).display()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_registered_example_answers() {
+        let inputs = Inputs::new(concat!(env!("CARGO_MANIFEST_DIR"), "/res"));
+        let answers = Answers::load(&inputs.dir().join(ANSWERS_FILE)).expect("valid answers");
+        for solution in solutions() {
+            for &id in solution.inputs.iter().filter(|input| input.is_test()) {
+                let input = inputs.load(id).expect("example input");
+                assert_eq!(
+                    answers.get(solution.day, solution.name, &id.to_string()),
+                    Some(&(solution.solve)(&input).expect("valid example input")),
+                    "day {} {} with input {id}",
+                    solution.day,
+                    solution.name
+                );
+            }
+        }
+    }
+}
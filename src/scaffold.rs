@@ -0,0 +1,359 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use crate::answers::ANSWERS_FILE;
+use crate::inputs::InputId;
+
+/// Variant name of the example input created for a new day.
+pub const EXAMPLE_VARIANT: &str = "test";
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// The day is already registered or its module already exists.
+    Exists(u8),
+    Io(PathBuf, std::io::Error),
+    /// The file does not have the expected layout, so the new day could not be registered in it.
+    Layout(PathBuf, &'static str),
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exists(day) => write!(f, "day {day} already exists"),
+            Self::Io(path, err) => write!(f, "could not access '{}': {err}", path.display()),
+            Self::Layout(path, expected) => write!(f, "'{}' does not contain {expected}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+/// A file created or changed by `new_day`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Created(PathBuf),
+    Updated(PathBuf),
+}
+
+/// Add day `day` to the repository at `root`: create its module from a template and placeholder inputs, and register
+/// it with the runner, the benchmarks, the expected answers and the input generators.
+///
+/// All files are checked before any is written, so a failure leaves the repository untouched. Existing input files
+/// are kept, e.g. a real input downloaded before.
+///
+/// # Errors
+///
+/// Fails if the day already exists, or if a file cannot be read, written or does not have the expected layout.
+pub fn new_day(root: &Path, day: u8, title: &str) -> Result<Vec<Change>, ScaffoldError> {
+    let module_path = root.join(format!("src/days/day{day}.rs"));
+    if crate::registry::day(day).is_some() || module_path.exists() {
+        return Err(ScaffoldError::Exists(day));
+    }
+
+    let days_path = root.join("src/days/mod.rs");
+    let days = register_day(&read(&days_path)?, day, title)
+        .ok_or_else(|| ScaffoldError::Layout(days_path.clone(), "a `days!` invocation"))?;
+    let generators_path = root.join("src/generate.rs");
+    let generators = register_generator(&read(&generators_path)?, day)
+        .ok_or_else(|| ScaffoldError::Layout(generators_path.clone(), "the `GENERATORS` list"))?;
+    let answers_path = root.join("res").join(ANSWERS_FILE);
+    let answers = register_answers(&read(&answers_path)?, day);
+
+    let mut files = vec![
+        (Change::Created(module_path), module(day)),
+        (Change::Updated(days_path), days),
+        (Change::Updated(generators_path), generators),
+        (Change::Updated(answers_path), answers),
+    ];
+    for id in [InputId::test(day, EXAMPLE_VARIANT), InputId::real(day)] {
+        let path = root.join("res").join(id.file_name());
+        if !path.exists() {
+            files.push((Change::Created(path), String::new()));
+        }
+    }
+
+    for (change, content) in &files {
+        let (Change::Created(path) | Change::Updated(path)) = change;
+        std::fs::write(path, content).map_err(|err| ScaffoldError::Io(path.clone(), err))?;
+    }
+    Ok(files.into_iter().map(|(change, _)| change).collect())
+}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    std::fs::read_to_string(path).map_err(|err| ScaffoldError::Io(path.to_owned(), err))
+}
+
+/// Source of the module of a new day: a `parse` function splitting the input into lines and placeholder solutions.
+pub fn module(day: u8) -> String {
+    format!(
+        r#"use crate::answer::Answer;
+use crate::parse::ParseError;
+
+pub fn parse(input: &str) -> Result<Vec<&str>, ParseError> {{
+    Ok(input.lines().collect())
+}}
+
+pub fn try_part1(input: &str) -> Result<Answer, ParseError> {{
+    Ok(part1(&parse(input)?).into())
+}}
+
+pub fn try_part2(input: &str) -> Result<Answer, ParseError> {{
+    Ok(part2(&parse(input)?).into())
+}}
+
+// TODO: solve day {day}.
+pub const fn part1(_lines: &[&str]) -> u64 {{
+    0
+}}
+
+// TODO: solve day {day}.
+pub const fn part2(_lines: &[&str]) -> u64 {{
+    0
+}}
+
+#[cfg(test)]
+mod test {{
+    use super::{{parse, part1, part2}};
+
+    // TODO: paste the example of the puzzle description, also stored in `res/day{day}_{EXAMPLE_VARIANT}.txt`.
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn test_part1() {{
+        assert_eq!(part1(&parse(EXAMPLE).expect("valid input")), 0);
+    }}
+
+    #[test]
+    fn test_part2() {{
+        assert_eq!(part2(&parse(EXAMPLE).expect("valid input")), 0);
+    }}
+}}
+"#
+    )
+}
+
+/// Insert the entry of `day` into the `days!` invocation of `src/days/mod.rs`, keeping the days ordered.
+/// `None` if `days` has no `days!` invocation.
+pub fn register_day(days: &str, day: u8, title: &str) -> Option<String> {
+    let start = days.find("days! {\n")?;
+    let end = start + days[start..].find("\n}")? + 1;
+    let title = title.replace('\\', "\\\\").replace('"', "\\\"");
+    let entry = format!(
+        "    {day} day{day} \"{title}\" {{\n        1 part1 [\"{EXAMPLE_VARIANT}\", real],\n        2 part2 [\"{EXAMPLE_VARIANT}\", real],\n    }}\n"
+    );
+    let at = line_starts(days, start..end)
+        .find(|&i| {
+            days[i..]
+                .strip_prefix("    ")
+                .and_then(|rest| leading_day(rest, ' '))
+                .is_some_and(|other| other > day)
+        })
+        .unwrap_or(end);
+    Some(insert(days, at, &entry))
+}
+
+/// Add a placeholder generator for `day` to `src/generate.rs`, keeping the `GENERATORS` and the generator functions
+/// ordered by day. `None` if `generators` has no `GENERATORS` list.
+pub fn register_generator(generators: &str, day: u8) -> Option<String> {
+    let start = generators.find("pub static GENERATORS")?;
+    let end = start + generators[start..].find("\n];")? + 1;
+    let entry = format!(
+        "    Generator {{\n        day: {day},\n        size: \"number of lines\",\n        default_size: 1000,\n        density: None,\n        default_density: 0.0,\n        generate: day{day},\n    }},\n"
+    );
+    let at = line_starts(generators, start..end)
+        .find(|&i| {
+            generators[i..]
+                .strip_prefix("        day: ")
+                .and_then(|rest| leading_day(rest, ','))
+                .is_some_and(|other| other > day)
+        })
+        // Back to the `Generator {` line of the entry.
+        .and_then(|i| generators[..i - 1].rfind('\n').map(|j| j + 1))
+        .unwrap_or(end);
+    let generators = insert(generators, at, &entry);
+
+    let function = format!(
+        "// TODO: generate inputs like the real one, and describe them here.\nconst fn day{day}(_rng: &mut Rng, _size: usize, _density: f64) -> String {{\n    String::new()\n}}\n\n"
+    );
+    let at = line_starts(&generators, 0..generators.len())
+        .find(|&i| {
+            generators[i..]
+                .strip_prefix("fn ")
+                .or_else(|| generators[i..].strip_prefix("const fn "))
+                .and_then(|rest| leading_day(rest, '('))
+                .is_some_and(|other| other > day)
+        })
+        .map(|i| item_start(&generators, i))
+        .or_else(|| generators.find("#[cfg(test)]\nmod test"))
+        .unwrap_or(generators.len());
+    Some(insert(&generators, at, &function))
+}
+
+/// Add placeholder answers of the example input of `day` to `answers.toml`, keeping the days ordered.
+pub fn register_answers(answers: &str, day: u8) -> String {
+    let entry = format!(
+        "[day{day}.part1]\n# TODO: the answer of the example from the puzzle description.\n{EXAMPLE_VARIANT} = 0\n\n[day{day}.part2]\n{EXAMPLE_VARIANT} = 0\n\n"
+    );
+    let at = line_starts(answers, 0..answers.len()).find(|&i| {
+        answers[i..]
+            .strip_prefix('[')
+            .and_then(|rest| leading_day(rest, '.'))
+            .is_some_and(|other| other > day)
+    });
+    match at {
+        Some(at) => insert(answers, at, &entry),
+        None => {
+            let mut answers = answers.trim_end().to_owned();
+            answers.push_str("\n\n");
+            answers.push_str(entry.trim_end());
+            answers.push('\n');
+            answers
+        }
+    }
+}
+
+/// The day `N` of a line starting with `dayN` (or just `N`) followed by `separator`.
+fn leading_day(line: &str, separator: char) -> Option<u8> {
+    let rest = line.strip_prefix("day").unwrap_or(line);
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    match digits > 0 && rest[digits..].starts_with(separator) {
+        true => rest[..digits].parse().ok(),
+        false => None,
+    }
+}
+
+/// Offsets of the lines starting in `range` of `text`.
+fn line_starts(text: &str, range: std::ops::Range<usize>) -> impl Iterator<Item = usize> + '_ {
+    let first = text[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    std::iter::once(first)
+        .chain(
+            text[first..range.end]
+                .match_indices('\n')
+                .map(move |(i, _)| first + i + 1),
+        )
+        .filter(move |&i| i < range.end)
+}
+
+/// Start of the item at line `line`, including the comments and attributes right above it.
+fn item_start(text: &str, mut line: usize) -> usize {
+    while line > 0 {
+        let previous = text[..line - 1].rfind('\n').map_or(0, |i| i + 1);
+        match text[previous..line].starts_with("//") || text[previous..line].starts_with("#[") {
+            true => line = previous,
+            false => break,
+        }
+    }
+    line
+}
+
+fn insert(text: &str, at: usize, content: &str) -> String {
+    let mut out = String::with_capacity(text.len() + content.len());
+    out.push_str(&text[..at]);
+    out.push_str(content);
+    out.push_str(&text[at..]);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{module, register_answers, register_day, register_generator};
+
+    const DAYS: &str = r#"use crate::registry::days;
+
+days! {
+    1 day1 "Trebuchet?!" {
+        1 part1 ["part1_test", real],
+        2 part2 ["part2_test", real],
+    }
+    3 day3 "Gear Ratios" {
+        1 part1 ["test", real],
+        2 part2 ["test", real],
+    }
+}
+"#;
+
+    const GENERATORS: &str = r"pub static GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        generate: day1,
+    },
+    Generator {
+        day: 3,
+        generate: day3,
+    },
+];
+
+/// Lines of calibration values.
+fn day1(rng: &mut Rng, lines: usize, _density: f64) -> String {
+    String::new()
+}
+
+/// A schematic.
+#[allow(clippy::cast_possible_truncation)]
+fn day3(rng: &mut Rng, size: usize, symbol_density: f64) -> String {
+    String::new()
+}
+
+#[cfg(test)]
+mod test {}
+";
+
+    const ANSWERS: &str = "# Expected answers.\n\n[day1.part1]\nreal = 1\n\n[day3.part1]\nreal = 3\n";
+
+    #[test]
+    fn test_register_day_in_order() {
+        let days = register_day(DAYS, 2, "Cube \"Conundrum\"").expect("registered");
+        let entry = "    2 day2 \"Cube \\\"Conundrum\\\"\" {\n        1 part1 [\"test\", real],\n        2 part2 [\"test\", real],\n    }\n    3 day3";
+        assert!(days.find("    1 day1") < days.find(entry));
+
+        let days = register_day(DAYS, 12, "Hot Springs").expect("registered");
+        assert!(days.ends_with("    12 day12 \"Hot Springs\" {\n        1 part1 [\"test\", real],\n        2 part2 [\"test\", real],\n    }\n}\n"));
+        assert_eq!(register_day("mod day1;", 2, "Two"), None);
+    }
+
+    #[test]
+    fn test_register_generator_in_order() {
+        let generators = register_generator(GENERATORS, 2).expect("registered");
+        let (list, functions) = generators.split_once("\n];").expect("generator list");
+        assert!(list.find("day: 1,") < list.find("day: 2,"));
+        assert!(list.find("    Generator {\n        day: 2,") < list.find("day: 3,"));
+        let function = functions.find("const fn day2(").expect("generator function");
+        assert!(functions.find("fn day1(") < Some(function));
+        // Before the doc comment and attributes of the next generator.
+        assert!(functions[function..].contains("\n}\n\n/// A schematic.\n#[allow"));
+
+        let generators = register_generator(GENERATORS, 12).expect("registered");
+        let (list, functions) = generators.split_once("\n];").expect("generator list");
+        assert!(list.ends_with("generate: day12,\n    },"));
+        assert!(functions.find("fn day3(") < functions.find("const fn day12("));
+        assert!(functions.find("const fn day12(") < functions.find("#[cfg(test)]"));
+    }
+
+    #[test]
+    fn test_register_answers_in_order() {
+        let answers = register_answers(ANSWERS, 2);
+        assert!(answers.find("real = 1") < answers.find("[day2.part1]"));
+        assert!(answers.contains("[day2.part2]\ntest = 0\n\n[day3.part1]"));
+        assert!(crate::answers::Answers::parse(&answers).is_ok());
+
+        let answers = register_answers(ANSWERS, 12);
+        assert!(answers.starts_with(ANSWERS));
+        assert!(answers.ends_with("real = 3\n\n[day12.part1]\n# TODO: the answer of the example from the puzzle description.\ntest = 0\n\n[day12.part2]\ntest = 0\n"));
+    }
+
+    #[test]
+    fn test_module_template() {
+        let module = module(13);
+        for function in [
+            "pub fn parse(",
+            "pub fn try_part1(",
+            "pub const fn part1(",
+            "pub const fn part2(",
+        ] {
+            assert!(module.contains(function), "{function} missing");
+        }
+        assert!(module.contains("res/day13_test.txt"));
+    }
+}
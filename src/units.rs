@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+/// Format `duration` in the largest unit it fills, e.g. `12.3 μs`.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_secs_f64() * 1e9;
+    match nanos {
+        n if n < 1e3 => format!("{n:.0} ns"),
+        n if n < 1e6 => format!("{:.1} μs", n / 1e3),
+        n if n < 1e9 => format!("{:.1} ms", n / 1e6),
+        n => format!("{:.2} s", n / 1e9),
+    }
+}
+
+/// Parse a duration as written by [`format_duration`]. Also accepts `µs` (micro sign) and `us`.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let (value, unit) = s.trim().split_once(' ')?;
+    let value = value.parse::<f64>().ok()?;
+    let factor = match unit.trim() {
+        "ns" => 1e-9,
+        "μs" | "µs" | "us" => 1e-6,
+        "ms" => 1e-3,
+        "s" => 1.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(value * factor).ok()
+}
+
+/// Format `bytes` in the largest binary unit it fills, e.g. `1.5 KiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    let bytes = bytes as f64;
+    match bytes {
+        b if b < 1024.0 => format!("{b:.0} B"),
+        b if b < 1024.0 * 1024.0 => format!("{:.1} KiB", b / 1024.0),
+        b if b < 1024.0 * 1024.0 * 1024.0 => format!("{:.1} MiB", b / (1024.0 * 1024.0)),
+        b => format!("{:.2} GiB", b / (1024.0 * 1024.0 * 1024.0)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_bytes, format_duration, parse_duration};
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_and_parse_duration() {
+        for (duration, formatted) in [
+            (Duration::from_nanos(815), "815 ns"),
+            (Duration::from_nanos(12_345), "12.3 μs"),
+            (Duration::from_micros(1_500), "1.5 ms"),
+            (Duration::from_millis(2_250), "2.25 s"),
+        ] {
+            assert_eq!(format_duration(duration), formatted);
+        }
+        assert_eq!(parse_duration("12.3 μs"), Some(Duration::from_nanos(12_300)));
+        assert_eq!(parse_duration("12.3 µs"), Some(Duration::from_nanos(12_300)));
+        assert_eq!(parse_duration("2.25 s"), Some(Duration::from_millis(2_250)));
+        assert_eq!(parse_duration("-"), None);
+        assert_eq!(parse_duration("3 parsecs"), None);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(64), "64 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
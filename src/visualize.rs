@@ -0,0 +1,249 @@
+use std::fmt::Write;
+
+use crate::geometry::Point;
+use crate::grid::Grid;
+
+/// One of the 8 standard terminal colors, plus bright black, which most terminals show as gray.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+impl Color {
+    /// Foreground color code of the ANSI escape sequence, the background one being 10 higher.
+    const fn code(self) -> u8 {
+        match self {
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::White => 37,
+            Self::Gray => 90,
+        }
+    }
+}
+
+/// How a cell is printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub const PLAIN: Self = Self {
+        color: None,
+        background: None,
+        bold: false,
+    };
+
+    pub const fn color(color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..Self::PLAIN
+        }
+    }
+
+    #[must_use]
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    #[must_use]
+    pub const fn on(self, background: Color) -> Self {
+        Self {
+            background: Some(background),
+            ..self
+        }
+    }
+
+    /// The ANSI escape sequence switching from any style to this one.
+    fn escape(self) -> String {
+        let mut codes = vec![String::from("0")];
+        if self.bold {
+            codes.push(String::from("1"));
+        }
+        codes.extend(self.color.map(|color| color.code().to_string()));
+        codes.extend(self.background.map(|color| (color.code() + 10).to_string()));
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+/// A character with its style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub symbol: char,
+    pub style: Style,
+}
+
+impl Cell {
+    pub const fn new(symbol: char, style: Style) -> Self {
+        Self { symbol, style }
+    }
+
+    pub const fn plain(symbol: char) -> Self {
+        Self::new(symbol, Style::PLAIN)
+    }
+}
+
+/// An annotated picture of a grid puzzle, printed inside a frame of box-drawing characters with a legend below.
+///
+/// ```text
+/// ┌─▼─┐
+/// │#··│
+/// ▶···│
+/// └───┘
+///  # galaxy
+/// ```
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    cells: Grid<Cell>,
+    /// Replacements of the left border, one per row.
+    row_marks: Vec<Option<Cell>>,
+    /// Replacements of the top border, one per column.
+    column_marks: Vec<Option<Cell>>,
+    legend: Vec<(Cell, String)>,
+}
+
+impl Canvas {
+    /// A canvas showing each cell of `grid` as returned by `cell`.
+    pub fn new<T>(grid: &Grid<T>, mut cell: impl FnMut(Point<usize>, &T) -> Cell) -> Self {
+        let cells = Grid::from_vec(grid.width(), grid.iter().map(|(pos, value)| cell(pos, value)).collect());
+        Self {
+            row_marks: vec![None; cells.height()],
+            column_marks: vec![None; cells.width()],
+            cells,
+            legend: Vec::new(),
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub const fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    /// Show `mark` on the left border of `row`.
+    ///
+    /// # Panics
+    ///
+    /// If `row` is outside of the canvas.
+    pub fn mark_row(&mut self, row: usize, mark: Cell) {
+        self.row_marks[row] = Some(mark);
+    }
+
+    /// Show `mark` on the top border of `col`.
+    ///
+    /// # Panics
+    ///
+    /// If `col` is outside of the canvas.
+    pub fn mark_column(&mut self, col: usize, mark: Cell) {
+        self.column_marks[col] = Some(mark);
+    }
+
+    /// Explain what `sample` means below the picture.
+    #[must_use]
+    pub fn legend(mut self, sample: Cell, label: impl Into<String>) -> Self {
+        self.legend.push((sample, label.into()));
+        self
+    }
+
+    /// The picture, with ANSI escape sequences for the styles if `colors` is set. Without colors, only the symbols
+    /// tell the cells apart.
+    pub fn render(&self, colors: bool) -> String {
+        let mut out = String::new();
+        let styled = |out: &mut String, cells: &mut dyn Iterator<Item = Cell>| {
+            let mut current = Style::PLAIN;
+            for cell in cells {
+                if colors && cell.style != current {
+                    out.push_str(&cell.style.escape());
+                    current = cell.style;
+                }
+                out.push(cell.symbol);
+            }
+            if colors && current != Style::PLAIN {
+                out.push_str(&Style::PLAIN.escape());
+            }
+        };
+        let border = |mark: Option<Cell>, line: char| mark.unwrap_or_else(|| Cell::plain(line));
+
+        out.push('┌');
+        styled(&mut out, &mut self.column_marks.iter().map(|&mark| border(mark, '─')));
+        out.push_str("┐\n");
+        for (row, cells) in self.cells.rows().enumerate() {
+            styled(&mut out, &mut std::iter::once(border(self.row_marks[row], '│')));
+            styled(&mut out, &mut cells.iter().copied());
+            out.push_str("│\n");
+        }
+        out.push('└');
+        out.push_str(&"─".repeat(self.width()));
+        out.push_str("┘\n");
+        for (sample, label) in &self.legend {
+            out.push(' ');
+            styled(&mut out, &mut std::iter::once(*sample));
+            let _ = writeln!(out, " {label}");
+        }
+        out
+    }
+}
+
+/// `count` followed by `singular` or `plural` to match it, for the labels of a legend.
+pub fn counted(count: usize, singular: &str, plural: &str) -> String {
+    match count {
+        1 => format!("{count} {singular}"),
+        _ => format!("{count} {plural}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{counted, Canvas, Cell, Color, Style};
+    use crate::grid::Grid;
+
+    fn canvas() -> Canvas {
+        let grid = Grid::parse("#..\n...\n", "a cell", |c| Some(c == '#')).expect("valid grid");
+        let galaxy = Cell::new('#', Style::color(Color::Yellow).bold());
+        let mut canvas = Canvas::new(&grid, |_, &galaxy_here| match galaxy_here {
+            true => galaxy,
+            false => Cell::plain('·'),
+        })
+        .legend(galaxy, "galaxy");
+        canvas.mark_row(1, Cell::new('▶', Style::color(Color::Blue)));
+        canvas.mark_column(1, Cell::new('▼', Style::color(Color::Blue)));
+        canvas
+    }
+
+    #[test]
+    fn test_render_plain() {
+        assert_eq!(canvas().render(false), "┌─▼─┐\n│#··│\n▶···│\n└───┘\n # galaxy\n");
+    }
+
+    #[test]
+    fn test_render_with_colors() {
+        let rendered = canvas().render(true);
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "┌─\x1b[0;34m▼\x1b[0m─┐");
+        assert_eq!(lines[1], "│\x1b[0;1;33m#\x1b[0m··│");
+        assert_eq!(lines[2], "\x1b[0;34m▶\x1b[0m···│");
+        assert_eq!(Style::color(Color::Red).on(Color::Gray).escape(), "\x1b[0;31;100m");
+    }
+
+    #[test]
+    fn test_counted() {
+        assert_eq!(counted(0, "galaxy", "galaxies"), "0 galaxies");
+        assert_eq!(counted(1, "galaxy", "galaxies"), "1 galaxy");
+        assert_eq!(counted(2, "galaxy", "galaxies"), "2 galaxies");
+    }
+}
@@ -0,0 +1,49 @@
+fn main() {
+    // One test per registered (day, part, input) triple, named like `day05::part2::real`, so that
+    // `cargo test day05` or `cargo test ::real` select a subset.
+    libtest_mimic::run(&libtest_mimic::Arguments::from_args(), trials()).exit();
+}
+
+use std::sync::Arc;
+
+use lib::answers::{Answers, ANSWERS_FILE};
+use lib::inputs::{InputId, Inputs};
+use lib::registry::Solution;
+use libtest_mimic::{Failed, Trial};
+
+/// Every registered solution against each of its inputs. Ignored if the input or its expected answer is missing,
+/// e.g. when the real inputs are not available.
+fn trials() -> Vec<Trial> {
+    let inputs = Inputs::from_env();
+    let answers_path = inputs.dir().join(ANSWERS_FILE);
+    let answers =
+        Arc::new(Answers::load(&answers_path).unwrap_or_else(|err| panic!("{}: {err}", answers_path.display())));
+
+    lib::registry::solutions()
+        .flat_map(|solution| solution.inputs.iter().map(move |&id| (solution, id)))
+        .map(|(solution, id)| {
+            let name = format!("day{:02}::{}::{id}", solution.day, solution.name);
+            let runnable = inputs.path(id).exists() && expected(&answers, solution, id).is_some();
+            let (inputs, answers) = (inputs.clone(), Arc::clone(&answers));
+            Trial::test(name, move || check(&inputs, &answers, solution, id))
+                .with_kind(if id.is_test() { "example" } else { "real" })
+                .with_ignored_flag(!runnable)
+        })
+        .collect()
+}
+
+fn expected<'a>(answers: &'a Answers, solution: &Solution, id: InputId) -> Option<&'a lib::answer::Answer> {
+    answers.get(solution.day, solution.name, &id.to_string())
+}
+
+fn check(inputs: &Inputs, answers: &Answers, solution: &Solution, id: InputId) -> Result<(), Failed> {
+    let input = inputs
+        .load(id)
+        .map_err(|err| format!("could not read {}: {err}", inputs.path(id).display()))?;
+    let expected = expected(answers, solution, id).ok_or("no expected answer")?;
+    let answer = (solution.solve)(&input).map_err(|err| format!("invalid input: {err}"))?;
+    match &answer == expected {
+        true => Ok(()),
+        false => Err(format!("expected {expected}, got {answer}").into()),
+    }
+}